@@ -1,60 +1,180 @@
-use crate::parse_field;
-use crate::parse_method;
+use crate::dialects::parse_field_in;
+use crate::dialects::parse_method_in;
+use crate::dialects::MemberDialect;
+use crate::dialects::DEFAULT_DIALECTS;
+use crate::elements::fail_on_member_errors;
+use crate::elements::line_terminator;
+use crate::elements::parse_body_members;
+use crate::elements::parse_body_opening;
+use crate::elements::parse_element_name;
+use crate::elements::parse_header_decorations;
+use crate::elements::peek_name_end;
+use crate::elements::ElementName;
+use crate::elements::MemberErrors;
+use crate::elements::DEFAULT_NAMESPACE_SEPARATOR;
+use crate::links::Hyperlink;
+use crate::modifiers::member_kind;
+use crate::modifiers::MemberKind;
+use crate::spans::many0_spanned;
+use crate::spans::Span;
+use crate::spans::Spanned;
+use crate::styles::ElementStyle;
 use crate::PlantUMLField;
 use crate::PlantUMLMethod;
-use nom::bytes::complete::tag;
-use nom::bytes::complete::take_while;
-use nom::character::streaming::char;
-use nom::multi::many0;
-use nom::sequence::delimited;
 use nom::sequence::terminated;
 use nom::IResult;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct PlantUMLClass<'a> {
     pub name: &'a str,
+    pub display_name: &'a str,
+    pub namespace: Vec<&'a str>,
+    pub style: Option<ElementStyle<'a>>,
+    pub stereotypes: Vec<&'a str>,
+    pub tags: Vec<&'a str>,
+    pub link: Option<Hyperlink<'a>>,
     pub fields: Vec<PlantUMLField<'a>>,
     pub methods: Vec<PlantUMLMethod<'a>>,
+    pub span: Span,
 }
 
-pub fn parse_class_name(element: &str) -> IResult<&str, &str> {
-    delimited(
-        tag("class "),
-        take_while(|c: char| !c.is_whitespace()),
-        tag(" {"),
-    )(element)
+impl Spanned for PlantUMLClass<'_> {
+    fn span(&self) -> Span {
+        self.span
+    }
+
+    fn shift(&mut self, offset: usize) {
+        self.span = self.span.shifted(offset);
+        for field in self.fields.iter_mut() {
+            field.shift(offset);
+        }
+        for method in self.methods.iter_mut() {
+            method.shift(offset);
+        }
+    }
+}
+
+/// A line of the body of a class.
+enum ClassMember<'a> {
+    Field(PlantUMLField<'a>),
+    Method(PlantUMLMethod<'a>),
+}
+
+impl Spanned for ClassMember<'_> {
+    fn span(&self) -> Span {
+        match self {
+            ClassMember::Field(field) => field.span(),
+            ClassMember::Method(method) => method.span(),
+        }
+    }
+
+    fn shift(&mut self, offset: usize) {
+        match self {
+            ClassMember::Field(field) => field.shift(offset),
+            ClassMember::Method(method) => method.shift(offset),
+        }
+    }
+}
+
+pub fn parse_class_name(element: &str) -> IResult<&str, ElementName<'_>> {
+    terminated(parse_element_name("class"), peek_name_end)(element)
 }
 
 pub fn parse_class_elements<'a, F, T>(parser: F, element: &'a str) -> IResult<&'a str, Vec<T>>
 where
     F: Fn(&'a str) -> IResult<&'a str, T>,
+    T: Spanned,
 {
-    many0(terminated(&parser, char('\n')))(element)
+    many0_spanned(terminated(&parser, line_terminator))(element)
 }
 
-pub fn parse_class(element: &str) -> IResult<&str, PlantUMLClass> {
-    let (rest, name) = parse_class_name(element.trim_start())?;
-    let (rest, fields) = parse_class_elements(parse_field, rest)?;
-    let (rest, methods) = parse_class_elements(parse_method, rest)?;
+pub fn parse_class(element: &str) -> IResult<&str, PlantUMLClass<'_>> {
+    parse_class_with_separator(element, Some(DEFAULT_NAMESPACE_SEPARATOR))
+}
 
-    Ok((
-        rest,
-        PlantUMLClass {
-            name,
-            fields,
-            methods,
-        },
+/// Parses a class whose name is split into namespaces with `separator`, see [`ElementName::qualify`].
+pub fn parse_class_with_separator<'a>(
+    element: &'a str, separator: Option<&str>,
+) -> IResult<&'a str, PlantUMLClass<'a>> {
+    fail_on_member_errors(parse_class_recovering(
+        element,
+        separator,
+        &DEFAULT_DIALECTS,
     ))
 }
 
+/// Like [`parse_class_with_separator`], but the members that can't be parsed are skipped and their
+/// errors returned with the class.
+pub fn parse_class_recovering<'a>(
+    element: &'a str, separator: Option<&str>, dialects: &[MemberDialect],
+) -> IResult<&'a str, (PlantUMLClass<'a>, MemberErrors<'a>)> {
+    let trimmed = element.trim_start();
+    let (rest, name) = parse_class_name(trimmed)?;
+    let (namespace, name) = name.qualify(separator);
+    let ElementName {
+        name, display_name, ..
+    } = name;
+    let (rest, decorations) = parse_header_decorations(rest)?;
+    let (rest, has_body) = parse_body_opening(rest)?;
+    let mut class = PlantUMLClass {
+        name,
+        display_name,
+        namespace,
+        style: decorations.style,
+        stereotypes: decorations.stereotypes,
+        tags: decorations.tags,
+        link: decorations.link,
+        fields: vec![],
+        methods: vec![],
+        span: Span::between(element, trimmed, rest),
+    };
+    if !has_body {
+        return Ok((rest, (class, vec![])));
+    }
+
+    // Fields come before methods, a field declared after a method is an error. Members marked with
+    // `{field}` or `{method}` are only parsed as that kind of member.
+    let mut has_methods = false;
+    let parse_member = |line| {
+        let kind = member_kind(line);
+        if !has_methods && kind != Some(MemberKind::Method) {
+            match terminated(|line| parse_field_in(dialects, line), line_terminator)(line) {
+                Ok((rest, field)) => return Ok((rest, ClassMember::Field(field))),
+                Err(e) if kind == Some(MemberKind::Field) => return Err(e),
+                Err(_) => {}
+            }
+        }
+        let (rest, method) =
+            terminated(|line| parse_method_in(dialects, line), line_terminator)(line)?;
+        has_methods = true;
+        Ok((rest, ClassMember::Method(method)))
+    };
+    let members_input = rest;
+    let (rest, (members, errors)) = parse_body_members(members_input, parse_member)?;
+    for mut member in members {
+        member.shift(Span::of(element, members_input).start);
+        match member {
+            ClassMember::Field(field) => class.fields.push(field),
+            ClassMember::Method(method) => class.methods.push(method),
+        }
+    }
+    class.span = Span::between(element, trimmed, rest);
+
+    Ok((rest, (class, errors)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::accessibilities::Accessibility;
+    use crate::dialects::ALL_DIALECTS;
     use crate::field::parse_field;
     use crate::methods::parse_method;
     use crate::methods::MethodArgument;
     use crate::modifiers::Modifier;
+    use crate::relationships::Stroke;
+    use crate::spans::Span;
+    use crate::styles::Background;
 
     #[test]
     fn parse_class_methods_works() {
@@ -71,17 +191,22 @@ mod tests {
                     name: "main",
                     return_type: "void",
                     modifier: Modifier::Static,
+                    link: None,
                     arguments: vec![MethodArgument {
                         name: "args",
-                        argument_type: "String[]"
-                    }]
+                        argument_type: "String[]",
+                        span: Span::new(22, 35)
+                    }],
+                    span: Span::new(1, 36)
                 },
                 PlantUMLMethod {
                     accessibility: Accessibility::Private,
                     name: "preguntarUsuario",
                     return_type: "AccionUsuario",
                     modifier: Modifier::None,
-                    arguments: vec![]
+                    link: None,
+                    arguments: vec![],
+                    span: Span::new(38, 72)
                 }
             ]
         )
@@ -99,13 +224,17 @@ mod tests {
                     accessibility: Accessibility::Private,
                     name: "analizador",
                     modifier: Modifier::None,
-                    field_type: "AnalizadorEquipos"
+                    link: None,
+                    field_type: "AnalizadorEquipos",
+                    span: Span::new(1, 32)
                 },
                 PlantUMLField {
                     accessibility: Accessibility::Private,
                     name: "analizador",
                     modifier: Modifier::None,
-                    field_type: "AnalizadorEquipos"
+                    link: None,
+                    field_type: "AnalizadorEquipos",
+                    span: Span::new(34, 65)
                 }
             ]
         );
@@ -116,8 +245,172 @@ mod tests {
     fn parse_class_name_works() {
         let input = "class VentanaPrograma {\n";
         let (rest, output) = parse_class_name(input).expect("Can't parse the class name");
-        assert_eq!(rest, "\n");
-        assert_eq!(output, "VentanaPrograma");
+        assert_eq!(rest, " {\n");
+        assert_eq!(output, ElementName::new("VentanaPrograma"));
+    }
+
+    #[test]
+    fn parse_class_name_fails() {
+        assert!(parse_class_name("class Ventana Programa {\n").is_err());
+        assert!(parse_class_name("class Ventana Programa{\n").is_err());
+        assert!(parse_class_name("class Ventana asdf\n").is_err());
+        assert!(parse_class_name("class Ventana <<Vista>>\n").is_ok());
+    }
+
+    #[test]
+    fn parse_class_without_body_works() {
+        let (rest, class) = parse_class("class Jugador\n").expect("Can't parse the class");
+        assert_eq!("\n", rest);
+        assert_eq!(
+            class,
+            PlantUMLClass {
+                name: "Jugador",
+                display_name: "Jugador",
+                namespace: vec![],
+                style: None,
+                stereotypes: vec![],
+                tags: vec![],
+                link: None,
+                fields: vec![],
+                methods: vec![],
+                span: Span::new(0, 13)
+            }
+        );
+
+        let (rest, class) = parse_class("class Vacio {}\n").expect("Can't parse the class");
+        assert_eq!("\n", rest);
+        assert_eq!(
+            class,
+            PlantUMLClass {
+                name: "Vacio",
+                display_name: "Vacio",
+                namespace: vec![],
+                style: None,
+                stereotypes: vec![],
+                tags: vec![],
+                link: None,
+                fields: vec![],
+                methods: vec![],
+                span: Span::new(0, 14)
+            }
+        );
+    }
+
+    #[test]
+    fn parse_class_with_flexible_header_works() {
+        for input in [
+            "class  Jugador{\n\t- nombre: String\n}\n",
+            "class\tJugador \t{ \n\t- nombre: String\n}\n",
+            "class Jugador\n{\n\t- nombre: String\n}\n",
+        ] {
+            let (rest, class) = parse_class(input).expect("Can't parse the class");
+            assert_eq!("\n", rest);
+            assert_eq!(
+                class,
+                PlantUMLClass {
+                    name: "Jugador",
+                    display_name: "Jugador",
+                    namespace: vec![],
+                    style: None,
+                    stereotypes: vec![],
+                    tags: vec![],
+                    link: None,
+                    fields: vec![PlantUMLField {
+                        accessibility: Accessibility::Private,
+                        modifier: Modifier::None,
+                        link: None,
+                        field_type: "String",
+                        name: "nombre",
+                        span: Span::new(input.find('-').unwrap(), input.len() - 3)
+                    }],
+                    methods: vec![],
+                    span: Span::new(0, input.len() - 1)
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn parse_class_with_crlf_works() {
+        let input = "class Jugador {\r\n\t- nombre: String\r\n\t+ void jugar()\r\n}\r\n";
+        let (rest, class) = parse_class(input).expect("Can't parse the class");
+        assert_eq!("\r\n", rest);
+        assert_eq!(
+            class,
+            PlantUMLClass {
+                name: "Jugador",
+                display_name: "Jugador",
+                namespace: vec![],
+                style: None,
+                stereotypes: vec![],
+                tags: vec![],
+                link: None,
+                fields: vec![PlantUMLField {
+                    accessibility: Accessibility::Private,
+                    modifier: Modifier::None,
+                    link: None,
+                    field_type: "String",
+                    name: "nombre",
+                    span: Span::new(18, 34)
+                }],
+                methods: vec![PlantUMLMethod {
+                    accessibility: Accessibility::Public,
+                    modifier: Modifier::None,
+                    link: None,
+                    name: "jugar",
+                    return_type: "void",
+                    arguments: vec![],
+                    span: Span::new(37, 51)
+                }],
+                span: Span::new(0, 54)
+            }
+        );
+    }
+
+    #[test]
+    fn parse_class_with_separator_works() {
+        let (_, class) = parse_class("class com.acme.Equipo\n").expect("Can't parse the class");
+        assert_eq!(class.namespace, vec!["com", "acme"]);
+        assert_eq!(class.name, "Equipo");
+        assert_eq!(class.display_name, "Equipo");
+
+        let (_, class) = parse_class_with_separator("class a::b::C {}\n", Some("::"))
+            .expect("Can't parse the class");
+        assert_eq!(class.namespace, vec!["a", "b"]);
+        assert_eq!(class.name, "C");
+
+        let (_, class) = parse_class_with_separator("class com.acme.Equipo\n", None)
+            .expect("Can't parse the class");
+        assert!(class.namespace.is_empty());
+        assert_eq!(class.name, "com.acme.Equipo");
+    }
+
+    #[test]
+    fn parse_class_with_style_works() {
+        let input = "class Equipo #pink;line:red;line.dashed;text:blue {\n\t- nombre: String\n}";
+        let (rest, class) = parse_class(input).expect("Can't parse the class");
+        assert_eq!("", rest);
+        assert_eq!(class.fields.len(), 1);
+        assert_eq!(
+            class.style,
+            Some(ElementStyle {
+                background: Some(Background::Color("pink")),
+                line_color: Some("red"),
+                line_style: Some(Stroke::Dashed),
+                text_color: Some("blue"),
+            })
+        );
+
+        let (_, class) = parse_class("class \"Cuenta\" as CB #back:palegreen|lightblue\n")
+            .expect("Can't parse the class");
+        assert_eq!(class.name, "CB");
+        assert!(class.style.is_some());
+    }
+
+    #[test]
+    fn parse_class_without_body_fails() {
+        assert!(parse_class("class Ventana Programa\n").is_err());
+        assert!(parse_class("class Vacio { - nombre: String }\n").is_err());
     }
 
     #[test]
@@ -128,37 +421,117 @@ mod tests {
             + AccionUsuario preguntarUsuario()
 }\n";
         let (rest, class) = parse_class(input).expect("Can't parse the class");
-        assert_eq!("}\n", rest);
+        assert_eq!("\n", rest);
         assert_eq!(
             class,
             PlantUMLClass {
                 name: "VentanaPrograma",
+                display_name: "VentanaPrograma",
+                namespace: vec![],
+                style: None,
+                stereotypes: vec![],
+                tags: vec![],
+                link: None,
                 fields: vec![PlantUMLField {
                     accessibility: Accessibility::Private,
                     modifier: Modifier::None,
+                    link: None,
                     field_type: "AnalizadorEquipos",
-                    name: "analizador"
+                    name: "analizador",
+                    span: Span::new(36, 67)
                 }],
                 methods: vec![
                     PlantUMLMethod {
                         accessibility: Accessibility::Public,
                         modifier: Modifier::Static,
+                        link: None,
                         name: "main",
                         return_type: "void",
                         arguments: vec![MethodArgument {
                             argument_type: "String[]",
-                            name: "args"
-                        }]
+                            name: "args",
+                            span: Span::new(101, 114)
+                        }],
+                        span: Span::new(80, 115)
                     },
                     PlantUMLMethod {
                         accessibility: Accessibility::Public,
                         modifier: Modifier::None,
+                        link: None,
                         name: "preguntarUsuario",
                         return_type: "AccionUsuario",
-                        arguments: vec![]
+                        arguments: vec![],
+                        span: Span::new(128, 162)
                     }
-                ]
+                ],
+                span: Span::new(0, 164)
             }
         )
     }
+
+    #[test]
+    fn parse_class_recovering_works() {
+        let input = "class Equipo {
+    - nombre Equipo: String
+    - puntos: int
+    + void jugar()
+    - goles: int
+    + int getPuntos()
+}
+";
+        let (rest, (class, errors)) = parse_class_recovering(input, None, &DEFAULT_DIALECTS)
+            .expect("Couldn't parse the class!");
+        assert_eq!(rest, "\n");
+        let fields: Vec<_> = class.fields.iter().map(|field| field.name).collect();
+        let methods: Vec<_> = class.methods.iter().map(|method| method.name).collect();
+        assert_eq!(fields, vec!["puntos"]);
+        assert_eq!(methods, vec!["jugar", "getPuntos"]);
+        assert_eq!(class.fields[0].span, Span::new(47, 60));
+        assert_eq!(class.span, Span::new(0, 120));
+
+        let lines: Vec<_> = errors
+            .into_iter()
+            .map(|error| match error {
+                nom::Err::Error(error) => error.input.lines().next(),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            lines,
+            vec![Some("- nombre Equipo: String"), Some("- goles: int")]
+        );
+        assert!(parse_class(input).is_err());
+    }
+
+    #[test]
+    fn parse_class_with_member_kinds_works() {
+        let input = "class Equipo {
+    - {field} nombre: String
+    - {field} void correr()
+    + {method} total: int
+    + void jugar()
+}
+";
+        let (_, (class, errors)) =
+            parse_class_recovering(input, None, &ALL_DIALECTS).expect("Couldn't parse the class!");
+        let fields: Vec<_> = class.fields.iter().map(|field| field.name).collect();
+        let methods: Vec<_> = class.methods.iter().map(|method| method.name).collect();
+        assert_eq!(fields, vec!["nombre"]);
+        assert_eq!(methods, vec!["jugar"]);
+
+        let lines: Vec<_> = errors
+            .into_iter()
+            .map(|error| match error {
+                nom::Err::Error(error) => error.input.lines().next(),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                Some("- {field} void correr()"),
+                Some("+ {method} total: int")
+            ]
+        );
+    }
 }
@@ -0,0 +1,385 @@
+use crate::errors::ParseError;
+use crate::errors::ParseWarning;
+use crate::messages::Label;
+use crate::messages::Language;
+use crate::parser::ParseOutput;
+use crate::sources::Source;
+use std::fmt::Write;
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const BOLD_RED: &str = "\x1b[1;31m";
+const BOLD_YELLOW: &str = "\x1b[1;33m";
+const BOLD_BLUE: &str = "\x1b[1;34m";
+
+/// Whether rendered diagnostics use ANSI colors.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum RenderStyle {
+    #[default]
+    Plain,
+    Color,
+}
+
+impl RenderStyle {
+    fn paint(&self, color: &'static str) -> &'static str {
+        match self {
+            RenderStyle::Plain => "",
+            RenderStyle::Color => color,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    fn label(&self) -> Label {
+        match self {
+            Severity::Error => Label::Error,
+            Severity::Warning => Label::Warning,
+        }
+    }
+
+    fn color(&self) -> &'static str {
+        match self {
+            Severity::Error => BOLD_RED,
+            Severity::Warning => BOLD_YELLOW,
+        }
+    }
+}
+
+/// Renders diagnostics like `rustc` does, with their code, the line where they were found and the
+/// span underlined:
+///
+/// ```text
+/// error[PUML0004]: invalid identifier while parsing the class
+///  --> equipo.puml:2:5
+///   |
+/// 2 |   - nombre Equipo: String
+///   |     ^^^^^^^^^^^^^
+///   |
+///   = help: field names cannot contain spaces
+/// ```
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct Renderer {
+    pub style: RenderStyle,
+    pub language: Language,
+}
+
+impl Renderer {
+    /// `source` has to be the input that was parsed, the one the span of the error points to.
+    pub fn render_error(&self, error: &ParseError, file_name: &str, source: &str) -> String {
+        self.render(Severity::Error, error, file_name, source)
+    }
+
+    pub fn render_warning(&self, warning: &ParseWarning, file_name: &str, source: &str) -> String {
+        self.render(Severity::Warning, warning, file_name, source)
+    }
+
+    /// Renders an error found by [`crate::parser::Parser::parse`] in the file of `source` it comes
+    /// from, which can be an included one.
+    pub fn render_error_in(&self, error: &ParseError, source: &Source) -> String {
+        let (file, error) = source.relocate(error);
+        self.render(Severity::Error, &error, &file.name, &file.contents)
+    }
+
+    pub fn render_warning_in(&self, warning: &ParseWarning, source: &Source) -> String {
+        let (file, warning) = source.relocate(warning);
+        self.render(Severity::Warning, &warning, &file.name, &file.contents)
+    }
+
+    /// Renders every error and then every warning of a parsed source, each one in the file it comes
+    /// from.
+    pub fn render_output(&self, output: &ParseOutput, source: &Source) -> String {
+        let errors = output
+            .errors
+            .iter()
+            .map(|error| self.render_error_in(error, source));
+        let warnings = output
+            .warnings
+            .iter()
+            .map(|warning| self.render_warning_in(warning, source));
+        errors.chain(warnings).collect::<Vec<_>>().join("\n")
+    }
+
+    fn render(
+        &self, severity: Severity, error: &ParseError, file_name: &str, source: &str,
+    ) -> String {
+        render(
+            severity,
+            error,
+            file_name,
+            source,
+            self.style,
+            self.language,
+        )
+    }
+}
+
+/// Renders an error in English, see [`Renderer`].
+pub fn render_error(
+    error: &ParseError, file_name: &str, source: &str, style: RenderStyle,
+) -> String {
+    render(
+        Severity::Error,
+        error,
+        file_name,
+        source,
+        style,
+        Language::English,
+    )
+}
+
+/// Renders a warning in English, see [`Renderer`].
+pub fn render_warning(
+    warning: &ParseWarning, file_name: &str, source: &str, style: RenderStyle,
+) -> String {
+    render(
+        Severity::Warning,
+        warning,
+        file_name,
+        source,
+        style,
+        Language::English,
+    )
+}
+
+fn render(
+    severity: Severity, error: &ParseError, file_name: &str, source: &str, style: RenderStyle,
+    language: Language,
+) -> String {
+    let span = error.span;
+    let (line, column) = span.line_column(source);
+    let line_start = source[..span.start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = source[span.start..]
+        .find('\n')
+        .map_or(source.len(), |i| span.start + i);
+    let mut text_start = line_start;
+    if line_start == 0 && source.starts_with('\u{feff}') {
+        text_start = '\u{feff}'.len_utf8();
+    }
+    let text = source[text_start..line_end].trim_end_matches('\r');
+
+    // Tabs are kept so the carets end up under the span whatever the width of a tab is.
+    let padding: String = source[text_start..span.start]
+        .chars()
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+    let underlined = source[span.start..span.end.min(line_end)]
+        .trim_end_matches('\r')
+        .chars()
+        .count();
+    let carets = "^".repeat(underlined.max(1));
+
+    let width = line.to_string().len();
+    let gutter = " ".repeat(width);
+    let color = style.paint(severity.color());
+    let blue = style.paint(BOLD_BLUE);
+    let bold = style.paint(BOLD);
+    let reset = style.paint(RESET);
+
+    let mut rendered = String::new();
+    let severity = language.label(severity.label());
+    let code = error.kind.code();
+    let message = language.message(error);
+    let _ = writeln!(
+        rendered,
+        "{color}{severity}[{code}]{reset}{bold}: {message}{reset}"
+    );
+    let _ = writeln!(
+        rendered,
+        "{gutter}{blue}-->{reset} {file_name}:{line}:{column}"
+    );
+    let _ = writeln!(rendered, "{gutter} {blue}|{reset}");
+    let _ = writeln!(rendered, "{blue}{line} |{reset} {text}");
+    let _ = writeln!(
+        rendered,
+        "{gutter} {blue}|{reset} {padding}{color}{carets}{reset}"
+    );
+    if let Some(help) = error.help {
+        let _ = writeln!(rendered, "{gutter} {blue}|{reset}");
+        let _ = writeln!(
+            rendered,
+            "{gutter} {blue}={reset} {bold}{label}{reset}: {help}",
+            label = language.label(Label::Help),
+            help = language.help(help),
+        );
+    }
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::ElementKind;
+    use crate::errors::Help;
+    use crate::errors::ParseErrorKind;
+    use crate::parse_uml_from_contents;
+    use crate::parse_uml_recovering;
+    use crate::parse_uml_with_warnings;
+    use crate::parser::Parser;
+    use crate::parser::ParserOptions;
+    use crate::sources::SourceFile;
+    use crate::spans::Span;
+
+    #[test]
+    fn render_error_works() {
+        let source = "class Equipo {\n  - nombre Equipo: String\n}\n";
+        let errors = parse_uml_from_contents(source).expect_err("PlantUML shouldn't be parsed!");
+        assert_eq!(
+            render_error(&errors[0], "equipo.puml", source, RenderStyle::Plain),
+            "error[PUML0004]: invalid identifier while parsing the class
+ --> equipo.puml:2:5
+  |
+2 |   - nombre Equipo: String
+  |     ^^^^^^^^^^^^^
+  |
+  = help: field names cannot contain spaces
+"
+        );
+    }
+
+    #[test]
+    fn render_error_without_help_works() {
+        let source = "\u{feff}class Ventana Programa {\r\n}\r\n";
+        let errors = parse_uml_from_contents(source).expect_err("PlantUML shouldn't be parsed!");
+        assert_eq!(
+            render_error(&errors[0], "ventana.puml", source, RenderStyle::Plain),
+            "error[PUML0001]: unexpected token while parsing the class
+ --> ventana.puml:1:15
+  |
+1 | class Ventana Programa {
+  |               ^^^^^^^^^^
+"
+        );
+    }
+
+    #[test]
+    fn render_error_at_the_end_works() {
+        let source = "note \"Sin final\" as N1\n\tnote as N2\n\tlínea";
+        let error = ParseError {
+            kind: ParseErrorKind::UnexpectedEnd,
+            element: ElementKind::Note,
+            text: "",
+            span: Span::new(source.len(), source.len()),
+            line: 3,
+            column: 7,
+            help: Some(Help::CloseNote),
+        };
+        assert_eq!(
+            render_error(&error, "notas.puml", source, RenderStyle::Plain),
+            "error[PUML0003]: unexpected end of input while parsing the note
+ --> notas.puml:3:7
+  |
+3 | \tlínea
+  | \t     ^
+  |
+  = help: add an `end note` line to close the note
+"
+        );
+    }
+
+    #[test]
+    fn render_error_with_color_works() {
+        let source = "class Ventana Programa {\n}\n";
+        let errors = parse_uml_from_contents(source).expect_err("PlantUML shouldn't be parsed!");
+        let rendered = render_error(&errors[0], "ventana.puml", source, RenderStyle::Color);
+        assert!(rendered.starts_with(
+            "\x1b[1;31merror[PUML0001]\x1b[0m\x1b[1m: unexpected token while parsing the class\x1b[0m\n"
+        ));
+        assert!(rendered.contains("\x1b[1;31m^^^^^^^^^^\x1b[0m"));
+    }
+
+    #[test]
+    fn render_warning_works() {
+        let source = "@startuml\nclas Equipo {\n}\n@enduml\n";
+        let (_, _, warnings) =
+            parse_uml_with_warnings(source).expect("PlantUML couldn't be parsed!");
+        assert_eq!(
+            render_warning(&warnings[0], "equipo.puml", source, RenderStyle::Plain),
+            "warning[PUML0006]: unrecognized line while parsing the diagram
+ --> equipo.puml:2:1
+  |
+2 | clas Equipo {
+  | ^^^^^^^^^^^^^
+  |
+  = help: did you mean `class`?
+"
+        );
+        let rendered = render_warning(&warnings[0], "equipo.puml", source, RenderStyle::Color);
+        assert!(rendered.starts_with("\x1b[1;33mwarning[PUML0006]\x1b[0m"));
+    }
+
+    #[test]
+    fn render_in_spanish_works() {
+        let source = "class Equipo {\n  - nombre Equipo: String\n}\nclas Jugador\n";
+        let (_, errors, warnings) = parse_uml_recovering(source);
+        let renderer = Renderer {
+            style: RenderStyle::Plain,
+            language: Language::Spanish,
+        };
+        assert_eq!(
+            renderer.render_error(&errors[0], "equipo.puml", source),
+            "error[PUML0004]: identificador no válido al analizar la clase
+ --> equipo.puml:2:5
+  |
+2 |   - nombre Equipo: String
+  |     ^^^^^^^^^^^^^
+  |
+  = ayuda: los nombres de los atributos no pueden contener espacios
+"
+        );
+        assert_eq!(
+            renderer.render_warning(&warnings[0], "equipo.puml", source),
+            "aviso[PUML0006]: línea no reconocida al analizar el diagrama
+ --> equipo.puml:4:1
+  |
+4 | clas Jugador
+  | ^^^^^^^^^^^^
+  |
+  = ayuda: ¿quisiste decir `class`?
+"
+        );
+    }
+
+    #[test]
+    fn render_in_included_file_works() {
+        let resolver = |_: &str, _: &str| {
+            Some(SourceFile::new(
+                "jugador.iuml",
+                "class Jugador {\n  - nombre Completo: String\n}\n",
+            ))
+        };
+        let parser = Parser::new(ParserOptions {
+            include_resolver: Some(Box::new(resolver)),
+            ..ParserOptions::default()
+        });
+        let source = parser.load("equipo.puml", "class Equipo\n!include jugador.iuml\n");
+        let output = parser.parse(&source);
+        assert_eq!(
+            Renderer::default().render_error_in(&output.errors[0], &source),
+            "error[PUML0004]: invalid identifier while parsing the class
+ --> jugador.iuml:2:5
+  |
+2 |   - nombre Completo: String
+  |     ^^^^^^^^^^^^^^^
+  |
+  = help: field names cannot contain spaces
+"
+        );
+    }
+
+    #[test]
+    fn render_output_works() {
+        let parser = Parser::default();
+        let source = parser.load("equipo.puml", "class Ventana Programa\nclas Equipo\n");
+        let output = parser.parse(&source);
+        let rendered = Renderer::default().render_output(&output, &source);
+        assert!(rendered.starts_with("error[PUML0001]"));
+        assert!(rendered.contains("\n\nwarning[PUML0006]"));
+        assert_eq!(rendered.matches(" --> equipo.puml:").count(), 2);
+    }
+}
@@ -0,0 +1,118 @@
+use crate::field::parse_field;
+use crate::field::parse_java_field;
+use crate::field::PlantUMLField;
+use crate::methods::parse_method;
+use crate::methods::parse_uml_method;
+use crate::methods::PlantUMLMethod;
+use nom::error::Error;
+use nom::error::ErrorKind;
+use nom::IResult;
+
+/// Syntax in which the members of classes and interfaces are written.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum MemberDialect {
+    /// Types before the names, like `- String nombre` and `+ void setNombre(String nombre)`.
+    Java,
+    /// Types after the names, like `- nombre: String` and `+ setNombre(nombre: String): void`.
+    Uml,
+    /// Fields like the ones of [`MemberDialect::Uml`] and methods like the ones of
+    /// [`MemberDialect::Java`], like `- nombre: String` together with `+ void jugar()`.
+    Mixed,
+}
+
+/// The dialects of [`crate::parse_uml_from_contents`] and the default [`crate::parser::ParserOptions`].
+pub const DEFAULT_DIALECTS: [MemberDialect; 1] = [MemberDialect::Mixed];
+
+/// Every dialect, in the order they're tried. The UML one goes first, so a field like
+/// `- nombre: String` is never read as a Java one.
+pub const ALL_DIALECTS: [MemberDialect; 2] = [MemberDialect::Uml, MemberDialect::Java];
+
+impl MemberDialect {
+    fn field_parser(&self) -> fn(&str) -> IResult<&str, PlantUMLField<'_>> {
+        match self {
+            MemberDialect::Java => parse_java_field,
+            MemberDialect::Uml | MemberDialect::Mixed => parse_field,
+        }
+    }
+
+    fn method_parser(&self) -> fn(&str) -> IResult<&str, PlantUMLMethod<'_>> {
+        match self {
+            MemberDialect::Java | MemberDialect::Mixed => parse_method,
+            MemberDialect::Uml => parse_uml_method,
+        }
+    }
+}
+
+/// Parses a field written in any of `dialects`. When none of them can parse it, the error is the one
+/// of the first dialect.
+pub fn parse_field_in<'a>(
+    dialects: &[MemberDialect], element: &'a str,
+) -> IResult<&'a str, PlantUMLField<'a>> {
+    parse_in(dialects, element, |dialect| dialect.field_parser())
+}
+
+/// Parses a method written in any of `dialects`. When none of them can parse it, the error is the one
+/// of the first dialect.
+pub fn parse_method_in<'a>(
+    dialects: &[MemberDialect], element: &'a str,
+) -> IResult<&'a str, PlantUMLMethod<'a>> {
+    parse_in(dialects, element, |dialect| dialect.method_parser())
+}
+
+fn parse_in<'a, T>(
+    dialects: &[MemberDialect], element: &'a str,
+    parser: impl Fn(&MemberDialect) -> fn(&'a str) -> IResult<&'a str, T>,
+) -> IResult<&'a str, T> {
+    let mut first_error = None;
+    for dialect in dialects {
+        match parser(dialect)(element) {
+            Ok(parsed) => return Ok(parsed),
+            Err(e) => {
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+    Err(first_error.unwrap_or_else(|| nom::Err::Error(Error::new(element, ErrorKind::Alt))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_field_in_works() {
+        let uml = "- nombre: String\n";
+        let java = "- String nombre\n";
+        assert_eq!(
+            parse_field_in(&ALL_DIALECTS, uml).map(|(_, field)| field.field_type),
+            Ok("String")
+        );
+        assert_eq!(
+            parse_field_in(&ALL_DIALECTS, java).map(|(_, field)| field.name),
+            Ok("nombre")
+        );
+        assert!(parse_field_in(&[MemberDialect::Uml], java).is_err());
+        assert!(parse_field_in(&[MemberDialect::Java], uml).is_err());
+        assert!(parse_field_in(&[], uml).is_err());
+        assert!(parse_field_in(&DEFAULT_DIALECTS, uml).is_ok());
+        assert!(parse_field_in(&DEFAULT_DIALECTS, java).is_err());
+    }
+
+    #[test]
+    fn parse_method_in_works() {
+        let uml = "+ jugar(minutos: int): void\n";
+        let java = "+ void jugar(int minutos)\n";
+        assert_eq!(
+            parse_method_in(&ALL_DIALECTS, uml).map(|(_, method)| method.return_type),
+            Ok("void")
+        );
+        assert_eq!(
+            parse_method_in(&ALL_DIALECTS, java).map(|(_, method)| method.return_type),
+            Ok("void")
+        );
+        assert!(parse_method_in(&[MemberDialect::Java], uml).is_err());
+        assert!(parse_method_in(&[MemberDialect::Uml], java).is_err());
+        assert!(parse_method_in(&DEFAULT_DIALECTS, java).is_ok());
+        assert!(parse_method_in(&DEFAULT_DIALECTS, uml).is_err());
+    }
+}
@@ -0,0 +1,615 @@
+use crate::links::parse_hyperlink;
+use crate::links::Hyperlink;
+use crate::spans::Span;
+use crate::spans::Spanned;
+use crate::styles::parse_element_style;
+use crate::styles::ElementStyle;
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::bytes::complete::take_till;
+use nom::bytes::complete::take_until;
+use nom::bytes::complete::take_while1;
+use nom::character::complete::char;
+use nom::character::complete::line_ending;
+use nom::character::complete::multispace0;
+use nom::character::complete::not_line_ending;
+use nom::character::complete::space0;
+use nom::character::complete::space1;
+use nom::combinator::eof;
+use nom::combinator::map;
+use nom::combinator::opt;
+use nom::combinator::peek;
+use nom::error::Error;
+use nom::error::ErrorKind;
+use nom::error::ParseError;
+use nom::sequence::delimited;
+use nom::sequence::pair;
+use nom::sequence::preceded;
+use nom::sequence::terminated;
+use nom::sequence::tuple;
+use nom::Err;
+use nom::IResult;
+
+/// Separator used to split qualified names when the diagram doesn't set one with
+/// `set namespaceSeparator`.
+pub const DEFAULT_NAMESPACE_SEPARATOR: &str = ".";
+
+/// The names an element can be referenced by.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ElementName<'a> {
+    /// Identifier of the element, it's the alias when the element declares one.
+    pub name: &'a str,
+    /// Name shown in the diagram, it's the same as `name` when the element doesn't have an alias.
+    pub display_name: &'a str,
+    /// Whether `name` was written between quotes, quoted names are never split into namespaces.
+    pub quoted: bool,
+}
+
+impl<'a> ElementName<'a> {
+    pub fn new(name: &'a str) -> ElementName<'a> {
+        ElementName {
+            name,
+            display_name: name,
+            quoted: false,
+        }
+    }
+
+    /// Splits a qualified name like `com.acme.Equipo` into its namespace (`["com", "acme"]`) and the
+    /// name of the element (`Equipo`). When the element doesn't have an alias the display name is
+    /// shortened too. Nothing is split if `separator` is `None` or the name was quoted.
+    pub fn qualify(self, separator: Option<&str>) -> (Vec<&'a str>, ElementName<'a>) {
+        let separator = match separator {
+            Some(separator) if !self.quoted && !separator.is_empty() => separator,
+            _ => return (vec![], self),
+        };
+        let mut namespace: Vec<&'a str> = self
+            .name
+            .split(separator)
+            .filter(|segment| !segment.is_empty())
+            .collect();
+        let name = match namespace.pop() {
+            Some(name) => name,
+            None => return (vec![], self),
+        };
+        let display_name = if self.display_name == self.name {
+            name
+        } else {
+            self.display_name
+        };
+
+        (
+            namespace,
+            ElementName {
+                name,
+                display_name,
+                quoted: false,
+            },
+        )
+    }
+}
+
+/// Parses a name between double quotes, the quotes are not included in the output.
+pub fn parse_quoted(element: &str) -> IResult<&str, &str> {
+    delimited(
+        char('"'),
+        take_till(|c: char| c == '"' || c == '\n'),
+        char('"'),
+    )(element)
+}
+
+/// Parses a quoted or unquoted name, the boolean is `true` when the name was quoted.
+fn parse_name_token(element: &str) -> IResult<&str, (&str, bool)> {
+    alt((
+        map(parse_quoted, |name| (name, true)),
+        map(
+            take_while1(|c: char| !c.is_whitespace() && c != '{' && c != '"'),
+            |name| (name, false),
+        ),
+    ))(element)
+}
+
+/// Creates a parser for the header of an element declared with `keyword` (`class`, `interface`...).
+///
+/// The keyword and the name can be separated by any amount of spaces or tabs, the name ends at the
+/// first whitespace or opening brace. Names with spaces must be quoted and an alias can be given
+/// with `as`, both `class "Cuenta Bancaria" as CB` and `class CB as "Cuenta Bancaria"` are valid.
+pub fn parse_element_name<'a>(
+    keyword: &'static str,
+) -> impl FnMut(&'a str) -> IResult<&'a str, ElementName<'a>> {
+    move |element: &'a str| {
+        let (rest, (first, first_quoted)) =
+            preceded(pair(tag(keyword), space1), parse_name_token)(element)?;
+        let (rest, alias) = opt(preceded(
+            tuple((space1, tag("as"), space1)),
+            parse_name_token,
+        ))(rest)?;
+
+        let name = match alias {
+            None => ElementName {
+                name: first,
+                display_name: first,
+                quoted: first_quoted,
+            },
+            Some((second, true)) if !first_quoted => ElementName {
+                name: first,
+                display_name: second,
+                quoted: false,
+            },
+            Some((second, second_quoted)) => ElementName {
+                name: second,
+                display_name: first,
+                quoted: second_quoted,
+            },
+        };
+        Ok((rest, name))
+    }
+}
+
+/// Checks, without consuming anything, that the name of an element is followed by something that can
+/// come after it: a decoration, the opening brace of its body or the end of the line.
+pub fn peek_name_end(element: &str) -> IResult<&str, ()> {
+    map(
+        peek(alt((
+            preceded(multispace0, tag("{")),
+            preceded(space1, alt((tag("<<"), tag("#"), tag("$"), tag("[[")))),
+            line_end,
+        ))),
+        |_| (),
+    )(element)
+}
+
+/// Everything that can be written between the name of an element and its body.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct HeaderDecorations<'a> {
+    pub style: Option<ElementStyle<'a>>,
+    /// Stereotypes like `<<Entity>>`, without the angle brackets.
+    pub stereotypes: Vec<&'a str>,
+    /// Tags like `$internal`, without the dollar sign.
+    pub tags: Vec<&'a str>,
+    pub link: Option<Hyperlink<'a>>,
+}
+
+enum HeaderDecoration<'a> {
+    Style(ElementStyle<'a>),
+    Stereotype(&'a str),
+    Tag(&'a str),
+    Link(Hyperlink<'a>),
+}
+
+/// Parses a stereotype like `<<Entity>>` and returns its name.
+pub fn parse_stereotype(element: &str) -> IResult<&str, &str> {
+    map(delimited(tag("<<"), take_until(">>"), tag(">>")), str::trim)(element)
+}
+
+/// Parses a tag like `$internal` and returns its name.
+pub fn parse_tag(element: &str) -> IResult<&str, &str> {
+    preceded(
+        char('$'),
+        take_while1(|c: char| c.is_alphanumeric() || c == '_' || c == '-'),
+    )(element)
+}
+
+/// Parses the decorations that follow the name of an element, like its inline style, stereotypes,
+/// tags and hyperlink.
+pub fn parse_header_decorations(element: &str) -> IResult<&str, HeaderDecorations<'_>> {
+    let mut decorations = HeaderDecorations::default();
+    let mut rest = element;
+    while let Ok((next, decoration)) = preceded(
+        space1,
+        alt((
+            map(parse_element_style, HeaderDecoration::Style),
+            map(parse_stereotype, HeaderDecoration::Stereotype),
+            map(parse_tag, HeaderDecoration::Tag),
+            map(parse_hyperlink, HeaderDecoration::Link),
+        )),
+    )(rest)
+    {
+        match decoration {
+            HeaderDecoration::Style(style) => decorations.style = Some(style),
+            HeaderDecoration::Stereotype(stereotype) => decorations.stereotypes.push(stereotype),
+            HeaderDecoration::Tag(tag) => decorations.tags.push(tag),
+            HeaderDecoration::Link(link) => decorations.link = Some(link),
+        }
+        rest = next;
+    }
+    Ok((rest, decorations))
+}
+
+/// Parses what comes after the name of an element.
+///
+/// Returns `true` when the element opens a body (`{` followed by a new line) whose members still need
+/// to be parsed. Returns `false` when the element is only declared (`class Jugador`) or has an empty
+/// body on the same line (`class Vacio {}`), in both cases nothing else needs to be parsed.
+///
+/// The opening brace can be placed on the line that follows the name.
+pub fn parse_body_opening(element: &str) -> IResult<&str, bool> {
+    let opening = preceded(multispace0, char::<_, nom::error::Error<&str>>('{'))(element);
+    if let Ok((rest, _)) = opening {
+        alt((
+            map(terminated(preceded(space0, char('}')), line_end), |_| false),
+            map(peek(preceded(space0, line_terminator)), |_| true),
+        ))(rest)
+    } else {
+        map(line_end, |_| false)(element)
+    }
+}
+
+/// Parses the closing brace of an element body, it can be preceded by any amount of whitespace.
+pub fn parse_body_closing(element: &str) -> IResult<&str, char> {
+    preceded(multispace0, char('}'))(element)
+}
+
+/// A comment of the diagram, only kept when [`crate::parser::ParserOptions::retain_trivia`] is set.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PlantUMLComment<'a> {
+    /// Text of the comment without its delimiters.
+    pub text: &'a str,
+    /// Where the whole comment is, delimiters included.
+    pub span: Span,
+}
+
+/// Parses a comment, either a line that starts with `'` or a block between `/'` and `'/`. Returns its
+/// text.
+pub fn parse_comment(element: &str) -> IResult<&str, &str> {
+    alt((
+        delimited(tag("/'"), take_until("'/"), tag("'/")),
+        preceded(char('\''), not_line_ending),
+    ))(element)
+}
+
+/// Checks if a line starts the declaration of an element with a body.
+pub fn declares_element(line: &str) -> bool {
+    ["class", "interface", "enum", "package", "namespace"]
+        .iter()
+        .any(|keyword| starts_with_keyword(line, keyword))
+}
+
+/// Checks if a line starts with `keyword` followed by whitespace, so `classes` doesn't start with
+/// `class`.
+pub fn starts_with_keyword(line: &str, keyword: &str) -> bool {
+    line.strip_prefix(keyword)
+        .is_some_and(|rest| rest.starts_with(char::is_whitespace))
+}
+
+/// Errors of the members of a body that were skipped, see [`parse_body_members`].
+pub type MemberErrors<'a> = Vec<Err<Error<&'a str>>>;
+
+/// Parses the members of a body until its closing brace, `parser` gets one line at a time. A line
+/// that it can't parse is skipped and reported in the returned errors, so one wrong member doesn't
+/// hide the rest of the element.
+///
+/// The closing brace can also be written at the end of the last member, like `AZUL}`. The spans of
+/// the members are relative to `element`. A missing closing brace is reported as an error too, the
+/// body then ends where the input does or where another element is declared.
+pub fn parse_body_members<'a, T: Spanned>(
+    element: &'a str, mut parser: impl FnMut(&'a str) -> IResult<&'a str, T>,
+) -> IResult<&'a str, (Vec<T>, MemberErrors<'a>)> {
+    let mut rest = element;
+    let mut members = vec![];
+    let mut errors = vec![];
+    loop {
+        if let Ok((rest, _)) = parse_body_closing(rest) {
+            return Ok((rest, (members, errors)));
+        }
+        let line = rest.trim_start();
+        if line.is_empty() || declares_element(line) {
+            errors.push(Err::Error(Error::from_error_kind(
+                &line[..0],
+                ErrorKind::Char,
+            )));
+            return Ok((line, (members, errors)));
+        }
+        let remaining = line;
+        let (line, next) = line.split_at(line.find('\n').map_or(line.len(), |i| i + 1));
+        let last_member = line
+            .trim_end()
+            .strip_suffix('}')
+            .filter(|member| !member.trim().is_empty());
+        if let Some(member) = last_member {
+            if let Ok((left, mut parsed)) = parser(member) {
+                if left.trim().is_empty() {
+                    parsed.shift(Span::of(element, member).start);
+                    members.push(parsed);
+                    return Ok((&remaining[member.len() + 1..], (members, errors)));
+                }
+            }
+        }
+        match parser(line) {
+            Ok((left, mut member)) if left.trim().is_empty() => {
+                member.shift(Span::of(element, line).start);
+                members.push(member);
+            }
+            _ => errors.push(Err::Error(Error::from_error_kind(line, ErrorKind::Char))),
+        }
+        rest = next;
+    }
+}
+
+/// Turns the result of a parser that skips wrong members into one that fails on the first of them.
+pub fn fail_on_member_errors<'a, T>(
+    result: IResult<&'a str, (T, MemberErrors<'a>)>,
+) -> IResult<&'a str, T> {
+    let (rest, (parsed, errors)) = result?;
+    match errors.into_iter().next() {
+        Some(error) => Err(error),
+        None => Ok((rest, parsed)),
+    }
+}
+
+/// Parses the end of a line, the end of the input is also accepted as one.
+pub fn line_terminator(element: &str) -> IResult<&str, &str> {
+    alt((line_ending, eof))(element)
+}
+
+/// Parses the lines of a block until `end <keyword>` or `end<keyword>` is found, like the text of a
+/// note or a legend. The lines are returned without indentation.
+pub fn parse_block_lines<'a>(element: &'a str, keyword: &str) -> IResult<&'a str, Vec<&'a str>> {
+    let mut rest = element;
+    let mut lines = vec![];
+    loop {
+        let (next, line) = terminated(not_line_ending, line_terminator)(rest)?;
+        let line = line.trim();
+        if line.strip_prefix("end").map(str::trim_start) == Some(keyword) {
+            return Ok((next, lines));
+        }
+        if next.is_empty() {
+            return Err(Err::Error(Error::from_error_kind(next, ErrorKind::Eof)));
+        }
+        lines.push(line);
+        rest = next;
+    }
+}
+
+/// Succeeds without consuming anything if only whitespace is left on the current line.
+fn line_end(element: &str) -> IResult<&str, &str> {
+    preceded(space0, peek(line_terminator))(element)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::parse_enum_variant;
+    use crate::enums::PlantUMLEnumVariant;
+
+    #[test]
+    fn parse_element_name_works() {
+        let mut parser = parse_element_name("class");
+        let equipo = ElementName::new("Equipo");
+        assert_eq!(parser("class Equipo {\n"), Ok((" {\n", equipo)));
+        assert_eq!(parser("class  Equipo{\n"), Ok(("{\n", equipo)));
+        assert_eq!(parser("class\tEquipo \t\n"), Ok((" \t\n", equipo)));
+    }
+
+    #[test]
+    fn parse_element_name_with_alias_works() {
+        let mut parser = parse_element_name("class");
+        let cuenta = ElementName {
+            name: "CB",
+            display_name: "Cuenta Bancaria",
+            quoted: false,
+        };
+        assert_eq!(
+            parser("class \"Cuenta Bancaria\" as CB {\n"),
+            Ok((" {\n", cuenta))
+        );
+        assert_eq!(
+            parser("class CB as \"Cuenta Bancaria\"\n"),
+            Ok(("\n", cuenta))
+        );
+        assert_eq!(
+            parser("class \"Año.Fiscal\"\n"),
+            Ok((
+                "\n",
+                ElementName {
+                    name: "Año.Fiscal",
+                    display_name: "Año.Fiscal",
+                    quoted: true
+                }
+            ))
+        );
+        assert_eq!(
+            parser("class Cuenta as CB\n"),
+            Ok((
+                "\n",
+                ElementName {
+                    name: "CB",
+                    display_name: "Cuenta",
+                    quoted: false
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_element_name_fails() {
+        let mut parser = parse_element_name("class");
+        assert!(parser("classEquipo {\n").is_err());
+        assert!(parser("class {\n").is_err());
+        assert!(parser("enum Equipo {\n").is_err());
+        assert!(parser("class \"Cuenta Bancaria {\n").is_err());
+    }
+
+    #[test]
+    fn qualify_works() {
+        let (namespace, name) = ElementName::new("com.acme.Equipo").qualify(Some("."));
+        assert_eq!(namespace, vec!["com", "acme"]);
+        assert_eq!(name, ElementName::new("Equipo"));
+
+        let (namespace, name) = ElementName::new("a::b::C").qualify(Some("::"));
+        assert_eq!(namespace, vec!["a", "b"]);
+        assert_eq!(name, ElementName::new("C"));
+
+        let aliased = ElementName {
+            name: "banco.CB",
+            display_name: "Cuenta Bancaria",
+            quoted: false,
+        };
+        let (namespace, name) = aliased.qualify(Some("."));
+        assert_eq!(namespace, vec!["banco"]);
+        assert_eq!(name.name, "CB");
+        assert_eq!(name.display_name, "Cuenta Bancaria");
+    }
+
+    #[test]
+    fn qualify_keeps_name_works() {
+        let name = ElementName::new("com.acme.Equipo");
+        assert_eq!(name.qualify(None), (vec![], name));
+        assert_eq!(name.qualify(Some("::")), (vec![], name));
+
+        let quoted = ElementName {
+            name: "Año.Fiscal",
+            display_name: "Año.Fiscal",
+            quoted: true,
+        };
+        assert_eq!(quoted.qualify(Some(".")), (vec![], quoted));
+    }
+
+    #[test]
+    fn parse_header_decorations_works() {
+        let (rest, decorations) = parse_header_decorations(" #pink;line:red {\n")
+            .expect("Couldn't parse the decorations");
+        assert_eq!(" {\n", rest);
+        assert_eq!(decorations.style.unwrap().line_color, Some("red"));
+
+        let (rest, decorations) =
+            parse_header_decorations(" {\n").expect("Couldn't parse the decorations");
+        assert_eq!(" {\n", rest);
+        assert_eq!(decorations, HeaderDecorations::default());
+    }
+
+    #[test]
+    fn parse_header_decorations_with_stereotypes_and_tags_works() {
+        let (rest, decorations) = parse_header_decorations(" <<Entity>> $internal #pink {\n")
+            .expect("Couldn't parse the decorations");
+        assert_eq!(" {\n", rest);
+        assert_eq!(decorations.stereotypes, vec!["Entity"]);
+        assert_eq!(decorations.tags, vec!["internal"]);
+        assert!(decorations.style.is_some());
+    }
+
+    #[test]
+    fn parse_block_lines_works() {
+        assert_eq!(
+            parse_block_lines("  uno\n  dos\nend legend\nclass Foo", "legend"),
+            Ok(("class Foo", vec!["uno", "dos"]))
+        );
+        assert_eq!(
+            parse_block_lines("uno\nendtitle", "title"),
+            Ok(("", vec!["uno"]))
+        );
+        assert!(parse_block_lines("uno\ndos\n", "title").is_err());
+    }
+
+    #[test]
+    fn parse_body_opening_works() {
+        assert_eq!(parse_body_opening(" {\n"), Ok(("\n", true)));
+        assert_eq!(parse_body_opening("{ \t\n"), Ok((" \t\n", true)));
+        assert_eq!(parse_body_opening("\n{\n"), Ok(("\n", true)));
+        assert_eq!(parse_body_opening(" \t\n"), Ok(("\n", false)));
+        assert_eq!(parse_body_opening(" {\r\n"), Ok(("\r\n", true)));
+        assert_eq!(parse_body_opening("\r\n{\r\n"), Ok(("\r\n", true)));
+        assert_eq!(parse_body_opening(" {}\r\n"), Ok(("\r\n", false)));
+        assert_eq!(parse_body_opening(" {}\n"), Ok(("\n", false)));
+        assert_eq!(parse_body_opening(" { }"), Ok(("", false)));
+        assert_eq!(parse_body_opening("\n"), Ok(("\n", false)));
+        assert_eq!(parse_body_opening(""), Ok(("", false)));
+    }
+
+    #[test]
+    fn parse_body_opening_fails() {
+        assert!(parse_body_opening(" asdf {\n").is_err());
+        assert!(parse_body_opening(" { SALIR }\n").is_err());
+    }
+
+    #[test]
+    fn line_terminator_works() {
+        assert_eq!(line_terminator("\nclass"), Ok(("class", "\n")));
+        assert_eq!(line_terminator("\r\nclass"), Ok(("class", "\r\n")));
+        assert_eq!(line_terminator(""), Ok(("", "")));
+        assert!(line_terminator("class").is_err());
+    }
+
+    #[test]
+    fn parse_body_closing_works() {
+        assert_eq!(parse_body_closing("\n    }\n"), Ok(("\n", '}')));
+    }
+
+    #[test]
+    fn parse_body_members_works() {
+        let input = "\n  ROJO\n  VERDE CLARO\n  AZUL\n}\nclass Equipo";
+        let (rest, (variants, errors)) =
+            parse_body_members(input, parse_enum_variant).expect("Couldn't parse the body!");
+        assert_eq!(rest, "\nclass Equipo");
+        assert_eq!(
+            variants,
+            vec![
+                PlantUMLEnumVariant {
+                    name: "ROJO",
+                    span: Span::new(3, 7),
+                },
+                PlantUMLEnumVariant {
+                    name: "AZUL",
+                    span: Span::new(24, 28),
+                },
+            ]
+        );
+        assert_eq!(
+            errors,
+            vec![Err::Error(Error::from_error_kind(
+                &input[10..22],
+                ErrorKind::Char
+            ))]
+        );
+    }
+
+    #[test]
+    fn parse_body_members_without_closing_brace_works() {
+        let input = "\n  ROJO\nclass Equipo {\n}\n";
+        let (rest, (variants, errors)) =
+            parse_body_members(input, parse_enum_variant).expect("Couldn't parse the body!");
+        assert_eq!(rest, "class Equipo {\n}\n");
+        assert_eq!(variants.len(), 1);
+        match errors.as_slice() {
+            [Err::Error(error)] => assert_eq!(Span::of(input, error.input), Span::new(8, 8)),
+            errors => panic!("Wrong errors: {:?}", errors),
+        }
+
+        let (rest, (_, errors)) = parse_body_members("\n  ROJO\n", parse_enum_variant).unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn parse_body_members_closed_after_the_last_member_works() {
+        let input = "\n  ROJO\n  AZUL}\nclass Equipo";
+        let (rest, (variants, errors)) =
+            parse_body_members(input, parse_enum_variant).expect("Couldn't parse the body!");
+        assert_eq!(rest, "\nclass Equipo");
+        assert!(errors.is_empty());
+        let names: Vec<_> = variants.iter().map(|variant| variant.name).collect();
+        assert_eq!(names, vec!["ROJO", "AZUL"]);
+        assert_eq!(variants[1].span, Span::new(10, 14));
+    }
+
+    #[test]
+    fn declares_element_works() {
+        assert!(declares_element("class Equipo {"));
+        assert!(declares_element("namespace\tcom.acme {"));
+        assert!(!declares_element("classes: List<Clase>"));
+        assert!(!declares_element("- class: String"));
+    }
+
+    #[test]
+    fn parse_comment_works() {
+        assert_eq!(
+            parse_comment("' Modelo de equipos\nclass Equipo"),
+            Ok(("\nclass Equipo", " Modelo de equipos"))
+        );
+        assert_eq!(
+            parse_comment("/' Varias\n líneas '/ class Equipo"),
+            Ok((" class Equipo", " Varias\n líneas "))
+        );
+        assert!(parse_comment("/' Sin final\nclass Equipo").is_err());
+        assert!(parse_comment("class Equipo").is_err());
+    }
+}
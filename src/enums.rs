@@ -1,46 +1,128 @@
-use nom::bytes::complete::tag;
-use nom::bytes::complete::take_till;
-use nom::bytes::complete::take_while;
-use nom::character::complete::char;
-use nom::multi::many0;
-use nom::sequence::delimited;
+use crate::elements::fail_on_member_errors;
+use crate::elements::line_terminator;
+use crate::elements::parse_body_members;
+use crate::elements::parse_body_opening;
+use crate::elements::parse_element_name;
+use crate::elements::parse_header_decorations;
+use crate::elements::peek_name_end;
+use crate::elements::ElementName;
+use crate::elements::MemberErrors;
+use crate::elements::DEFAULT_NAMESPACE_SEPARATOR;
+use crate::links::Hyperlink;
+use crate::spans::Span;
+use crate::spans::Spanned;
+use crate::styles::ElementStyle;
+use nom::bytes::complete::take_till1;
 use nom::sequence::terminated;
 use nom::IResult;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct PlantUMLEnum<'a> {
     pub name: &'a str,
+    pub display_name: &'a str,
+    pub namespace: Vec<&'a str>,
+    pub style: Option<ElementStyle<'a>>,
+    pub stereotypes: Vec<&'a str>,
+    pub tags: Vec<&'a str>,
+    pub link: Option<Hyperlink<'a>>,
     pub variants: Vec<PlantUMLEnumVariant<'a>>,
+    pub span: Span,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+impl Spanned for PlantUMLEnum<'_> {
+    fn span(&self) -> Span {
+        self.span
+    }
+
+    fn shift(&mut self, offset: usize) {
+        self.span = self.span.shifted(offset);
+        for variant in self.variants.iter_mut() {
+            variant.shift(offset);
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct PlantUMLEnumVariant<'a> {
     pub name: &'a str,
+    pub span: Span,
 }
 
-pub fn parse_enum_variant(element: &str) -> IResult<&str, PlantUMLEnumVariant> {
+impl Spanned for PlantUMLEnumVariant<'_> {
+    fn span(&self) -> Span {
+        self.span
+    }
+
+    fn shift(&mut self, offset: usize) {
+        self.span = self.span.shifted(offset);
+    }
+}
+
+pub fn parse_enum_variant(element: &str) -> IResult<&str, PlantUMLEnumVariant<'_>> {
     let (rest, name) = terminated(
-        take_till(|c: char| c.is_whitespace() || c == '}'),
-        char('\n'),
+        take_till1(|c: char| c.is_whitespace() || c == '}'),
+        line_terminator,
     )(element.trim_start())?;
-    Ok((rest, PlantUMLEnumVariant { name }))
+    let span = Span::of(element, name);
+    Ok((rest, PlantUMLEnumVariant { name, span }))
 }
 
-pub fn parse_enum(element: &str) -> IResult<&str, PlantUMLEnum> {
-    let (rest, name) = delimited(
-        tag("enum "),
-        take_while(|c: char| !c.is_whitespace()),
-        tag(" {"),
-    )(element)?;
+pub fn parse_enum_name(element: &str) -> IResult<&str, ElementName<'_>> {
+    terminated(parse_element_name("enum"), peek_name_end)(element)
+}
 
-    let (rest, variants) = terminated(many0(parse_enum_variant), tag("}"))(rest)?;
+pub fn parse_enum(element: &str) -> IResult<&str, PlantUMLEnum<'_>> {
+    parse_enum_with_separator(element, Some(DEFAULT_NAMESPACE_SEPARATOR))
+}
+
+/// Parses an enum whose name is split into namespaces with `separator`, see [`ElementName::qualify`].
+pub fn parse_enum_with_separator<'a>(
+    element: &'a str, separator: Option<&str>,
+) -> IResult<&'a str, PlantUMLEnum<'a>> {
+    fail_on_member_errors(parse_enum_recovering(element, separator))
+}
+
+/// Like [`parse_enum_with_separator`], but the variants that can't be parsed are skipped and their errors
+/// returned with the enum.
+pub fn parse_enum_recovering<'a>(
+    element: &'a str, separator: Option<&str>,
+) -> IResult<&'a str, (PlantUMLEnum<'a>, MemberErrors<'a>)> {
+    let trimmed = element.trim_start();
+    let (rest, name) = parse_enum_name(trimmed)?;
+    let (namespace, name) = name.qualify(separator);
+    let ElementName {
+        name, display_name, ..
+    } = name;
+    let (rest, decorations) = parse_header_decorations(rest)?;
+    let (rest, has_body) = parse_body_opening(rest)?;
+    let mut enu = PlantUMLEnum {
+        name,
+        display_name,
+        namespace,
+        style: decorations.style,
+        stereotypes: decorations.stereotypes,
+        tags: decorations.tags,
+        link: decorations.link,
+        variants: vec![],
+        span: Span::between(element, trimmed, rest),
+    };
+    if !has_body {
+        return Ok((rest, (enu, vec![])));
+    }
 
-    Ok((rest, PlantUMLEnum { name, variants }))
+    let variants_input = rest;
+    let (rest, (variants, errors)) = parse_body_members(variants_input, parse_enum_variant)?;
+    enu.variants = variants;
+    enu.shift(Span::of(element, variants_input).start);
+    enu.span = Span::between(element, trimmed, rest);
+
+    Ok((rest, (enu, errors)))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::spans::Span;
 
     #[test]
     fn parse_enum_succeeds() {
@@ -55,19 +137,75 @@ mod tests {
             enu,
             PlantUMLEnum {
                 name: "AccionUsuario",
+                display_name: "AccionUsuario",
+                namespace: vec![],
+                style: None,
+                stereotypes: vec![],
+                tags: vec![],
+                link: None,
                 variants: vec![
-                    PlantUMLEnumVariant { name: "SALIR" },
                     PlantUMLEnumVariant {
-                        name: "TOTAL_GOLES"
+                        name: "SALIR",
+                        span: Span::new(25, 30)
+                    },
+                    PlantUMLEnumVariant {
+                        name: "TOTAL_GOLES",
+                        span: Span::new(35, 46)
                     },
                     PlantUMLEnumVariant {
-                        name: "TOTAL_TIROS_ESQUINA"
+                        name: "TOTAL_TIROS_ESQUINA",
+                        span: Span::new(51, 70)
                     },
-                ]
+                ],
+                span: Span::new(0, 72)
             }
         )
     }
 
+    #[test]
+    fn parse_enum_with_flexible_header_succeeds() {
+        let input = "enum\tColor  \n{\n    ROJO\n}";
+        let (rest, enu) = parse_enum(input).expect("Couldn't parse the enum!");
+        assert_eq!("", rest);
+        assert_eq!(
+            enu,
+            PlantUMLEnum {
+                name: "Color",
+                display_name: "Color",
+                namespace: vec![],
+                style: None,
+                stereotypes: vec![],
+                tags: vec![],
+                link: None,
+                variants: vec![PlantUMLEnumVariant {
+                    name: "ROJO",
+                    span: Span::new(19, 23)
+                }],
+                span: Span::new(0, 25)
+            }
+        );
+    }
+
+    #[test]
+    fn parse_enum_with_style_succeeds() {
+        let (_, enu) = parse_enum("enum Color #line:red;text:blue {\n    ROJO\n}")
+            .expect("Couldn't parse the enum!");
+        let style = enu.style.expect("The enum should have a style");
+        assert_eq!(style.line_color, Some("red"));
+        assert_eq!(style.text_color, Some("blue"));
+        assert_eq!(enu.variants.len(), 1);
+    }
+
+    #[test]
+    fn parse_enum_closed_after_the_last_variant_works() {
+        let (rest, enu) =
+            parse_enum("enum E {\n A\n B}\nclass C").expect("Couldn't parse the enum!");
+        assert_eq!("\nclass C", rest);
+        let names: Vec<_> = enu.variants.iter().map(|variant| variant.name).collect();
+        assert_eq!(names, vec!["A", "B"]);
+        assert_eq!(enu.span, Span::new(0, 15));
+    }
+
     #[test]
     fn parse_enum_fails() {
         let input = "enum Accion Usuario {
@@ -76,7 +214,7 @@ mod tests {
     TOTAL_TIROS_ESQUINA
 }";
         let res = parse_enum(input);
-        assert_eq!(true, res.is_err());
+        assert!(res.is_err());
 
         let input = "enum Accion Usuario {
     SALIR
@@ -84,14 +222,72 @@ mod tests {
     TOTAL_TIROS_ESQUINA
 }";
         let res = parse_enum(input);
-        assert_eq!(true, res.is_err());
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn parse_enum_name_works() {
+        let (rest, name) = parse_enum_name("enum Color {\n").expect("Couldn't parse the name!");
+        assert_eq!(" {\n", rest);
+        assert_eq!(ElementName::new("Color"), name);
+        assert!(parse_enum_name("enum Color #red\n").is_ok());
+    }
+
+    #[test]
+    fn parse_enum_name_fails() {
+        assert!(parse_enum_name("enum Accion Usuario {\n").is_err());
+        assert!(parse_enum_name("enum Accion Usuario{\n").is_err());
+        assert!(parse_enum_name("enum Color asdf\n").is_err());
+    }
+
+    #[test]
+    fn parse_enum_without_body_succeeds() {
+        let (rest, enu) = parse_enum("enum Color\n").expect("Couldn't parse the enum!");
+        assert_eq!("\n", rest);
+        assert_eq!(
+            enu,
+            PlantUMLEnum {
+                name: "Color",
+                display_name: "Color",
+                namespace: vec![],
+                style: None,
+                stereotypes: vec![],
+                tags: vec![],
+                link: None,
+                variants: vec![],
+                span: Span::new(0, 10)
+            }
+        );
+
+        let (rest, enu) = parse_enum("enum Color {}\n").expect("Couldn't parse the enum!");
+        assert_eq!("\n", rest);
+        assert_eq!(
+            enu,
+            PlantUMLEnum {
+                name: "Color",
+                display_name: "Color",
+                namespace: vec![],
+                style: None,
+                stereotypes: vec![],
+                tags: vec![],
+                link: None,
+                variants: vec![],
+                span: Span::new(0, 13)
+            }
+        );
     }
 
     #[test]
     fn parse_enum_variant_succeeds() {
         let (rest, output) =
             parse_enum_variant("\tSALIR\n").expect("Can't parse `SALIR` enum variant");
-        assert_eq!(output, PlantUMLEnumVariant { name: "SALIR" });
+        assert_eq!(
+            output,
+            PlantUMLEnumVariant {
+                name: "SALIR",
+                span: Span::new(1, 6)
+            }
+        );
         assert_eq!("", rest);
 
         let (rest, output) =
@@ -99,7 +295,36 @@ mod tests {
         assert_eq!(
             output,
             PlantUMLEnumVariant {
-                name: "TOTAL_GOLES"
+                name: "TOTAL_GOLES",
+                span: Span::new(1, 12)
+            }
+        );
+        assert_eq!("", rest);
+    }
+
+    #[test]
+    fn parse_enum_variant_with_crlf_succeeds() {
+        let (rest, output) =
+            parse_enum_variant("\tSALIR\r\n").expect("Can't parse `SALIR` enum variant");
+        assert_eq!(
+            output,
+            PlantUMLEnumVariant {
+                name: "SALIR",
+                span: Span::new(1, 6)
+            }
+        );
+        assert_eq!("", rest);
+    }
+
+    #[test]
+    fn parse_enum_variant_at_end_of_input_succeeds() {
+        let (rest, output) =
+            parse_enum_variant("\tSALIR").expect("Can't parse `SALIR` enum variant");
+        assert_eq!(
+            output,
+            PlantUMLEnumVariant {
+                name: "SALIR",
+                span: Span::new(1, 6)
             }
         );
         assert_eq!("", rest);
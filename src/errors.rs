@@ -0,0 +1,447 @@
+use crate::field::parse_field;
+use crate::messages::Language;
+use crate::modifiers::parse_modifier;
+use crate::modifiers::MODIFIERS;
+use crate::spans::Span;
+use crate::suggestions::closest;
+use std::error::Error;
+use std::fmt;
+
+/// What went wrong while parsing an element.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ParseErrorKind {
+    /// Something was found where it wasn't expected, like the second word of `class Ventana Programa`.
+    UnexpectedToken,
+    /// The input ended before the closing brace of the body of an element.
+    MissingBrace,
+    /// The input ended before a block, like the text of a note, was finished.
+    UnexpectedEnd,
+    /// A name that can't be used, like an argument name with spaces.
+    InvalidIdentifier,
+    /// A member modifier that isn't one of [`MODIFIERS`].
+    UnknownModifier,
+    /// A line that isn't any of the supported constructs, like `clas Foo {`.
+    UnrecognizedLine,
+    /// A `}` that doesn't close any block.
+    UnmatchedBrace,
+    /// A preprocessor line like `!define` that isn't supported, or an `!include` that wasn't expanded
+    /// because there's no [`crate::sources::IncludeResolver`].
+    UnsupportedPreprocessor,
+    /// A relationship with an element that isn't declared but whose name is close to one that is.
+    /// PlantUML creates undeclared elements, so it's only a warning, even in a strict parse.
+    UnresolvedReference,
+    /// An `!include` of a file that couldn't be found.
+    UnresolvedInclude,
+    /// An `!include` nested deeper than [`crate::parser::ParserOptions::max_include_depth`], usually
+    /// because a file includes itself.
+    IncludeTooDeep,
+}
+
+/// Every kind of error, in the order of their codes. New kinds go at the end with the next code, so
+/// the codes of the existing ones never change.
+const KINDS: [ParseErrorKind; 11] = [
+    ParseErrorKind::UnexpectedToken,
+    ParseErrorKind::MissingBrace,
+    ParseErrorKind::UnexpectedEnd,
+    ParseErrorKind::InvalidIdentifier,
+    ParseErrorKind::UnknownModifier,
+    ParseErrorKind::UnrecognizedLine,
+    ParseErrorKind::UnmatchedBrace,
+    ParseErrorKind::UnsupportedPreprocessor,
+    ParseErrorKind::UnresolvedReference,
+    ParseErrorKind::UnresolvedInclude,
+    ParseErrorKind::IncludeTooDeep,
+];
+
+impl ParseErrorKind {
+    /// Stable code of the kind, like `PUML0001`. It doesn't depend on the language of the messages,
+    /// so it can be used to look up or filter errors.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ParseErrorKind::UnexpectedToken => "PUML0001",
+            ParseErrorKind::MissingBrace => "PUML0002",
+            ParseErrorKind::UnexpectedEnd => "PUML0003",
+            ParseErrorKind::InvalidIdentifier => "PUML0004",
+            ParseErrorKind::UnknownModifier => "PUML0005",
+            ParseErrorKind::UnrecognizedLine => "PUML0006",
+            ParseErrorKind::UnmatchedBrace => "PUML0007",
+            ParseErrorKind::UnsupportedPreprocessor => "PUML0008",
+            ParseErrorKind::UnresolvedReference => "PUML0009",
+            ParseErrorKind::UnresolvedInclude => "PUML0010",
+            ParseErrorKind::IncludeTooDeep => "PUML0011",
+        }
+    }
+
+    /// Whether a strict parse turns warnings of this kind into errors. Undeclared references are
+    /// valid PlantUML, so they're always just warnings.
+    pub fn is_strict_error(&self) -> bool {
+        *self != ParseErrorKind::UnresolvedReference
+    }
+
+    /// The kind with the given code.
+    pub fn from_code(code: &str) -> Option<ParseErrorKind> {
+        KINDS.into_iter().find(|kind| kind.code() == code)
+    }
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(Language::English.kind(*self))
+    }
+}
+
+/// A short suggestion on how to fix an error.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Help<'a> {
+    FieldNameWithSpaces,
+    ArgumentNameWithSpaces,
+    VariantNameWithSpaces,
+    /// The body of an element has to be closed with `}`.
+    CloseBody,
+    /// A note has to be closed with `end note`.
+    CloseNote,
+    KnownModifiers,
+    /// What was probably meant instead of something misspelled.
+    DidYouMean(&'a str),
+}
+
+impl fmt::Display for Help<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&Language::English.help(*self))
+    }
+}
+
+/// The kind of element that was being parsed when an error was found.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ElementKind {
+    Class,
+    Interface,
+    Enum,
+    Package,
+    Note,
+    Diamond,
+    Group,
+    Relationship,
+    /// The diagram itself, for lines that aren't part of any element.
+    Diagram,
+}
+
+impl ElementKind {
+    fn has_body(&self) -> bool {
+        matches!(
+            self,
+            ElementKind::Class
+                | ElementKind::Interface
+                | ElementKind::Enum
+                | ElementKind::Package
+                | ElementKind::Group
+        )
+    }
+}
+
+impl fmt::Display for ElementKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ElementKind::Class => "class",
+            ElementKind::Interface => "interface",
+            ElementKind::Enum => "enum",
+            ElementKind::Package => "package",
+            ElementKind::Note => "note",
+            ElementKind::Diamond => "diamond",
+            ElementKind::Group => "together group",
+            ElementKind::Relationship => "relationship",
+            ElementKind::Diagram => "diagram",
+        };
+        f.write_str(name)
+    }
+}
+
+/// An error found while parsing a diagram.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ParseError<'a> {
+    pub kind: ParseErrorKind,
+    pub element: ElementKind,
+    /// The first line of the input where the error was found, without surrounding whitespace. It's
+    /// empty when the input ended unexpectedly.
+    pub text: &'a str,
+    /// Where `text` is in the parsed input.
+    pub span: Span,
+    /// 1-based line of the start of `span`.
+    pub line: usize,
+    /// 1-based column of the start of `span`, counted in characters.
+    pub column: usize,
+    pub help: Option<Help<'a>>,
+}
+
+/// Something in the diagram that was ignored, like a line that isn't any of the supported constructs.
+/// Warnings are errors that don't stop the parsing, a strict parse turns them into errors.
+pub type ParseWarning<'a> = ParseError<'a>;
+
+impl<'a> ParseError<'a> {
+    /// An error about `text`, which has to be a part of `source`.
+    pub fn new(
+        source: &'a str, kind: ParseErrorKind, element: ElementKind, text: &'a str,
+        help: Option<Help<'a>>,
+    ) -> Self {
+        let span = Span::of(source, text);
+        let (line, column) = span.line_column(source);
+        ParseError {
+            kind,
+            element,
+            text,
+            span,
+            line,
+            column,
+            help,
+        }
+    }
+
+    /// Turns the error returned by the parser of an element into a [`ParseError`]. `source` is the
+    /// whole input, the input of the nom error has to be a part of it.
+    pub fn from_nom(
+        source: &'a str, element: ElementKind, error: nom::Err<nom::error::Error<&'a str>>,
+    ) -> Self {
+        let (input, nom_kind) = match error {
+            nom::Err::Error(error) | nom::Err::Failure(error) => (error.input, error.code),
+            nom::Err::Incomplete(_) => (&source[source.len()..], nom::error::ErrorKind::Eof),
+        };
+        let input = input.trim_start();
+        let mut text = input[..input.find('\n').unwrap_or(input.len())].trim_end();
+
+        let (kind, help) = if text.is_empty() {
+            if element.has_body() {
+                (ParseErrorKind::MissingBrace, Some(Help::CloseBody))
+            } else if element == ElementKind::Note {
+                (ParseErrorKind::UnexpectedEnd, Some(Help::CloseNote))
+            } else {
+                (ParseErrorKind::UnexpectedEnd, None)
+            }
+        } else if let Some(modifier) = unknown_modifier(text) {
+            let help = match closest(modifier, MODIFIERS) {
+                Some(suggestion) => Help::DidYouMean(suggestion),
+                None => Help::KnownModifiers,
+            };
+            (ParseErrorKind::UnknownModifier, Some(help))
+        } else if let Some((name, help)) = find_name_with_spaces(element, text) {
+            text = name;
+            (ParseErrorKind::InvalidIdentifier, Some(help))
+        } else if nom_kind == nom::error::ErrorKind::Fail {
+            (ParseErrorKind::InvalidIdentifier, None)
+        } else {
+            (ParseErrorKind::UnexpectedToken, None)
+        };
+        ParseError::new(source, kind, element, text, help)
+    }
+
+    /// The error without its location, like `unexpected token while parsing the class`.
+    pub fn message(&self) -> String {
+        Language::English.message(self)
+    }
+}
+
+/// Finds the modifier between braces a member line starts with when it isn't a known one.
+fn unknown_modifier(line: &str) -> Option<&str> {
+    let member = line.trim_start_matches(['+', '-', '#', '~']).trim_start();
+    let end = member.find('}')?;
+    (member.starts_with('{') && parse_modifier(member).is_err()).then_some(&member[..=end])
+}
+
+/// Looks for a field, argument or enum variant name with spaces in a line of the body of an
+/// element, the parsers of the members stop there without saying why.
+fn find_name_with_spaces(element: ElementKind, line: &str) -> Option<(&str, Help<'_>)> {
+    match element {
+        ElementKind::Enum if !line.contains(['{', '}']) && line.contains(char::is_whitespace) => {
+            Some((line, Help::VariantNameWithSpaces))
+        }
+        ElementKind::Class | ElementKind::Interface if line.contains('(') => {
+            let start = line.find('(')? + 1;
+            let end = line.rfind(')').filter(|end| *end >= start)?;
+            line[start..end].split(',').find_map(|argument| {
+                let (_, name) = argument.trim().split_once(char::is_whitespace)?;
+                let name = name.trim();
+                name.contains(char::is_whitespace)
+                    .then_some((name, Help::ArgumentNameWithSpaces))
+            })
+        }
+        ElementKind::Class | ElementKind::Interface => match parse_field(line) {
+            Err(nom::Err::Error(error))
+                if error.code == nom::error::ErrorKind::Fail
+                    && error.input.trim().contains(char::is_whitespace) =>
+            {
+                Some((error.input.trim(), Help::FieldNameWithSpaces))
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+impl fmt::Display for ParseError<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message())?;
+        if !self.text.is_empty() {
+            write!(f, ": `{}`", self.text)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for ParseError<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nom::error::ErrorKind;
+
+    fn nom_error(input: &str, code: ErrorKind) -> nom::Err<nom::error::Error<&str>> {
+        nom::Err::Error(nom::error::Error { input, code })
+    }
+
+    #[test]
+    fn from_nom_works() {
+        let source = "class Jugador {\n  + {final} void jugar()\n";
+        let error = ParseError::from_nom(
+            source,
+            ElementKind::Class,
+            nom_error(&source[source.len()..], ErrorKind::Char),
+        );
+        assert_eq!(error.kind, ParseErrorKind::MissingBrace);
+        assert_eq!(error.text, "");
+        assert_eq!((error.line, error.column), (3, 1));
+
+        let error = ParseError::from_nom(
+            source,
+            ElementKind::Note,
+            nom_error(&source[source.len() - 1..], ErrorKind::Eof),
+        );
+        assert_eq!(error.kind, ParseErrorKind::UnexpectedEnd);
+
+        let error = ParseError::from_nom(
+            source,
+            ElementKind::Class,
+            nom_error(&source[15..], ErrorKind::Char),
+        );
+        assert_eq!(error.kind, ParseErrorKind::UnknownModifier);
+        assert_eq!(error.text, "+ {final} void jugar()");
+        assert_eq!(error.span, Span::new(18, 40));
+        assert_eq!((error.line, error.column), (2, 3));
+
+        let source = "class Ventana Programa {\n  - nombre Equipo: String\n}";
+        let error = ParseError::from_nom(
+            source,
+            ElementKind::Class,
+            nom_error(&source[14..], ErrorKind::Char),
+        );
+        assert_eq!(error.kind, ParseErrorKind::UnexpectedToken);
+        assert_eq!(error.text, "Programa {");
+        assert_eq!((error.line, error.column), (1, 15));
+
+        let error = ParseError::from_nom(
+            source,
+            ElementKind::Class,
+            nom_error(&source[36..], ErrorKind::Fail),
+        );
+        assert_eq!(error.kind, ParseErrorKind::InvalidIdentifier);
+        assert_eq!(error.text, "Equipo: String");
+    }
+
+    #[test]
+    fn from_nom_finds_names_with_spaces() {
+        let source = "class Equipo {\n  - nombre Equipo: String\n}";
+        let error = ParseError::from_nom(
+            source,
+            ElementKind::Class,
+            nom_error(&source[14..], ErrorKind::Char),
+        );
+        assert_eq!(error.kind, ParseErrorKind::InvalidIdentifier);
+        assert_eq!(error.text, "nombre Equipo");
+        assert_eq!(error.span, Span::new(19, 32));
+        assert_eq!(error.help, Some(Help::FieldNameWithSpaces));
+
+        let source = "interface Comparable {\n  + int comparar(Object otro objeto)\n}";
+        let error = ParseError::from_nom(
+            source,
+            ElementKind::Interface,
+            nom_error(&source[22..], ErrorKind::Char),
+        );
+        assert_eq!(error.kind, ParseErrorKind::InvalidIdentifier);
+        assert_eq!(error.text, "otro objeto");
+        assert_eq!(error.help, Some(Help::ArgumentNameWithSpaces));
+
+        let source = "class Equipo {\n  - nombre: \n}";
+        let error = ParseError::from_nom(
+            source,
+            ElementKind::Class,
+            nom_error(&source[14..], ErrorKind::Char),
+        );
+        assert_eq!(error.kind, ParseErrorKind::UnexpectedToken);
+        assert_eq!(error.help, None);
+    }
+
+    #[test]
+    fn from_nom_suggests_modifiers() {
+        let source = "class Jugador {\n  + {statc} void jugar()\n  + {final} void parar()\n}";
+        let error = ParseError::from_nom(
+            source,
+            ElementKind::Class,
+            nom_error(&source[15..], ErrorKind::Char),
+        );
+        assert_eq!(error.kind, ParseErrorKind::UnknownModifier);
+        assert_eq!(error.help, Some(Help::DidYouMean("{static}")));
+        assert_eq!(
+            error.help.map(|help| help.to_string()),
+            Some("did you mean `{static}`?".to_string())
+        );
+
+        let error = ParseError::from_nom(
+            source,
+            ElementKind::Class,
+            nom_error(&source[40..], ErrorKind::Char),
+        );
+        assert_eq!(error.text, "+ {final} void parar()");
+        assert_eq!(error.help, Some(Help::KnownModifiers));
+    }
+
+    #[test]
+    fn codes_work() {
+        assert_eq!(ParseErrorKind::UnexpectedToken.code(), "PUML0001");
+        assert_eq!(ParseErrorKind::UnresolvedReference.code(), "PUML0009");
+        assert_eq!(ParseErrorKind::IncludeTooDeep.code(), "PUML0011");
+        for (i, kind) in KINDS.into_iter().enumerate() {
+            assert_eq!(ParseErrorKind::from_code(kind.code()), Some(kind));
+            assert_eq!(kind.code(), format!("PUML{:04}", i + 1));
+        }
+        assert_eq!(ParseErrorKind::from_code("PUML0000"), None);
+    }
+
+    #[test]
+    fn display_works() {
+        let error = ParseError {
+            kind: ParseErrorKind::UnexpectedToken,
+            element: ElementKind::Class,
+            text: "Programa {",
+            span: Span::new(14, 24),
+            line: 1,
+            column: 15,
+            help: None,
+        };
+        assert_eq!(
+            error.to_string(),
+            "1:15: unexpected token while parsing the class: `Programa {`"
+        );
+
+        let error = ParseError {
+            kind: ParseErrorKind::MissingBrace,
+            element: ElementKind::Interface,
+            text: "",
+            span: Span::new(30, 30),
+            line: 4,
+            column: 1,
+            help: Some(Help::CloseBody),
+        };
+        assert_eq!(
+            error.to_string(),
+            "4:1: missing closing brace while parsing the interface"
+        );
+    }
+}
@@ -1,8 +1,13 @@
 use crate::accessibilities::parse_accessibility;
 use crate::accessibilities::Accessibility;
+use crate::links::split_trailing_hyperlink;
+use crate::links::Hyperlink;
 use crate::modifiers::parse_modifier;
 use crate::modifiers::Modifier;
+use crate::spans::Span;
+use crate::spans::Spanned;
 use nom::bytes::complete::take_until;
+use nom::character::complete::not_line_ending;
 use nom::character::complete::space0;
 use nom::character::streaming::char;
 use nom::error::Error;
@@ -13,15 +18,27 @@ use nom::sequence::preceded;
 use nom::Err;
 use nom::IResult;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct PlantUMLField<'a> {
     pub name: &'a str,
     pub field_type: &'a str,
     pub accessibility: Accessibility,
     pub modifier: Modifier,
+    pub link: Option<Hyperlink<'a>>,
+    pub span: Span,
 }
 
-pub fn doesnt_have_spaces<'a>(element: (&'a str, &'a str)) -> IResult<&str, &str> {
+impl Spanned for PlantUMLField<'_> {
+    fn span(&self) -> Span {
+        self.span
+    }
+
+    fn shift(&mut self, offset: usize) {
+        self.span = self.span.shifted(offset);
+    }
+}
+
+pub fn doesnt_have_spaces<'a>(element: (&'a str, &'a str)) -> IResult<&'a str, &'a str> {
     if !element.1.contains(char::is_whitespace) && !element.1.is_empty() {
         Ok(element)
     } else {
@@ -32,11 +49,13 @@ pub fn doesnt_have_spaces<'a>(element: (&'a str, &'a str)) -> IResult<&str, &str
     }
 }
 
-pub fn parse_field(element: &str) -> IResult<&str, PlantUMLField> {
-    let (rest, accessibility) = parse_accessibility(element.trim_start())?;
+pub fn parse_field(element: &str) -> IResult<&str, PlantUMLField<'_>> {
+    let trimmed = element.trim_start();
+    let (rest, accessibility) = parse_accessibility(trimmed)?;
     let (rest, modifier) = parse_modifier(rest.trim_start())?;
     let (rest, name) = parse_field_name(rest.trim_start())?;
     let (rest, field_type) = parse_field_type(rest.trim_start())?;
+    let (field_type, link) = split_trailing_hyperlink(field_type);
 
     Ok((
         rest,
@@ -45,18 +64,53 @@ pub fn parse_field(element: &str) -> IResult<&str, PlantUMLField> {
             field_type,
             accessibility,
             modifier,
+            link,
+            span: Span::between(element, trimmed, rest),
         },
     ))
 }
 
+/// Parses a field written with the type before the name, like `- String nombre`.
+pub fn parse_java_field(element: &str) -> IResult<&str, PlantUMLField<'_>> {
+    let trimmed = element.trim_start();
+    let (rest, accessibility) = parse_accessibility(trimmed)?;
+    let (rest, modifier) = parse_modifier(rest.trim_start())?;
+    let (rest, declaration) = not_line_ending(rest.trim_start())?;
+    let (declaration, link) = split_trailing_hyperlink(declaration);
+    let is_word = |word: &str| !word.contains([':', '(', ')', '{', '}']);
+
+    let mut words = declaration.split_whitespace();
+    match (words.next(), words.next(), words.next()) {
+        (Some(field_type), Some(name), None) if is_word(field_type) && is_word(name) => Ok((
+            rest,
+            PlantUMLField {
+                name,
+                field_type,
+                accessibility,
+                modifier,
+                link,
+                span: Span::between(element, trimmed, rest),
+            },
+        )),
+        _ => Err(Err::Error(Error::from_error_kind(
+            declaration,
+            ErrorKind::Verify,
+        ))),
+    }
+}
+
 pub fn parse_field_name(element: &str) -> IResult<&str, &str> {
     let until_colon = take_until(":")(element)?;
-    doesnt_have_spaces(until_colon)
+    let (rest, name) = doesnt_have_spaces(until_colon)?;
+    if name.contains(['(', ')']) {
+        return Err(Err::Error(Error::from_error_kind(name, ErrorKind::Verify)));
+    }
+    Ok((rest, name))
 }
 
 pub fn parse_field_type(element: &str) -> IResult<&str, &str> {
     let left_delimiter = pair(char(':'), space0);
-    let (rest, f_type) = preceded(left_delimiter, take_until("\n"))(element)?;
+    let (rest, f_type) = preceded(left_delimiter, not_line_ending)(element)?;
     let f_type = f_type.trim();
     if !f_type.is_empty() {
         Ok((rest, f_type))
@@ -68,6 +122,7 @@ pub fn parse_field_type(element: &str) -> IResult<&str, &str> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::spans::Span;
 
     #[test]
     fn parse_plantuml_field() {
@@ -81,11 +136,58 @@ mod tests {
                 name: "nombreEquipo",
                 field_type: "String",
                 accessibility: Accessibility::Private,
-                modifier: Modifier::None
+                modifier: Modifier::None,
+                link: None,
+                span: Span::new(1, 23)
             }
         )
     }
 
+    #[test]
+    fn parse_field_with_hyperlink() {
+        let (_, field) =
+            parse_field("- nombre: String [[https://wiki/Nombre{Nombre único}]]\n").unwrap();
+        assert_eq!(field.field_type, "String");
+        assert_eq!(
+            field.link,
+            Some(Hyperlink {
+                url: Some("https://wiki/Nombre"),
+                tooltip: Some("Nombre único"),
+                label: None,
+            })
+        );
+    }
+
+    #[test]
+    fn parse_java_field_works() {
+        let input = "\t- {static} String[] nombres [[https://wiki/Nombres]]\n";
+        let (rest, field) = parse_java_field(input).unwrap();
+        assert_eq!(rest, "\n");
+        assert_eq!(
+            field,
+            PlantUMLField {
+                name: "nombres",
+                field_type: "String[]",
+                accessibility: Accessibility::Private,
+                modifier: Modifier::Static,
+                link: Some(Hyperlink {
+                    url: Some("https://wiki/Nombres"),
+                    tooltip: None,
+                    label: None,
+                }),
+                span: Span::new(1, 53)
+            }
+        );
+    }
+
+    #[test]
+    fn parse_java_field_fails() {
+        assert!(parse_java_field("- nombre: String\n").is_err());
+        assert!(parse_java_field("+ void jugar()\n").is_err());
+        assert!(parse_java_field("- String nombre equipo\n").is_err());
+        assert!(parse_java_field("- nombre\n").is_err());
+    }
+
     //PARSE FIELD TYPE
     #[test]
     fn parse_type() {
@@ -95,6 +197,13 @@ mod tests {
         assert_eq!(output, ("\n", "String"));
     }
     #[test]
+    fn parse_type_with_crlf() {
+        let input = ": String\r\n";
+        let output = parse_field_type(input).unwrap();
+
+        assert_eq!(output, ("\r\n", "String"));
+    }
+    #[test]
     fn parse_field_type_fails() {
         let input = ": ";
         let output = parse_field_type(input);
@@ -118,6 +227,10 @@ mod tests {
         assert!(output.is_err());
     }
     #[test]
+    fn name_cant_have_parentheses() {
+        assert!(parse_field_name("getNombre(): String").is_err());
+    }
+    #[test]
     fn name_cant_have_spaces() {
         let input = "nombre Equipo: String";
         let output = parse_field_name(input);
@@ -0,0 +1,58 @@
+use crate::elements::parse_body_opening;
+use nom::bytes::complete::tag;
+use nom::IResult;
+
+/// Elements declared inside a `together { }` block. It only affects the layout of the diagram, so it
+/// doesn't change the namespace of its elements.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PlantUMLGroup<'a> {
+    /// Index in [`crate::PlantUMLFile::groups`] of the group this one is nested in.
+    pub parent: Option<usize>,
+    /// Names of the elements declared inside the group.
+    pub elements: Vec<&'a str>,
+}
+
+/// Parses the header of a `together` block.
+///
+/// The boolean is `true` when the group opens a body, the elements that follow belong to the group
+/// until its closing brace is found.
+pub fn parse_together(
+    element: &str, parent: Option<usize>,
+) -> IResult<&str, (PlantUMLGroup<'_>, bool)> {
+    let (rest, _) = tag("together")(element.trim_start())?;
+    let (rest, has_body) = parse_body_opening(rest)?;
+    let group = PlantUMLGroup {
+        parent,
+        elements: vec![],
+    };
+    Ok((rest, (group, has_body)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_together_works() {
+        let (rest, (group, has_body)) =
+            parse_together("together {\nclass A", Some(0)).expect("Couldn't parse the group!");
+        assert_eq!("\nclass A", rest);
+        assert!(has_body);
+        assert_eq!(
+            group,
+            PlantUMLGroup {
+                parent: Some(0),
+                elements: vec![],
+            }
+        );
+
+        let (_, (_, has_body)) = parse_together("together {}\n", None).unwrap();
+        assert!(!has_body);
+    }
+
+    #[test]
+    fn parse_together_fails() {
+        assert!(parse_together("together class A {\n", None).is_err());
+        assert!(parse_together("togethe {\n", None).is_err());
+    }
+}
@@ -1,32 +1,107 @@
-use crate::parse_method;
+use crate::dialects::parse_method_in;
+use crate::dialects::MemberDialect;
+use crate::dialects::DEFAULT_DIALECTS;
+use crate::elements::fail_on_member_errors;
+use crate::elements::line_terminator;
+use crate::elements::parse_body_members;
+use crate::elements::parse_body_opening;
+use crate::elements::parse_element_name;
+use crate::elements::parse_header_decorations;
+use crate::elements::peek_name_end;
+use crate::elements::ElementName;
+use crate::elements::MemberErrors;
+use crate::elements::DEFAULT_NAMESPACE_SEPARATOR;
+use crate::links::Hyperlink;
+use crate::spans::Span;
+use crate::spans::Spanned;
+use crate::styles::ElementStyle;
 use crate::PlantUMLMethod;
-use nom::bytes::complete::tag;
-use nom::bytes::complete::take_while;
-use nom::character::complete::char;
-use nom::multi::many0;
-use nom::sequence::delimited;
 use nom::sequence::terminated;
 use nom::IResult;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct PlantUMLInterface<'a> {
     pub name: &'a str,
+    pub display_name: &'a str,
+    pub namespace: Vec<&'a str>,
+    pub style: Option<ElementStyle<'a>>,
+    pub stereotypes: Vec<&'a str>,
+    pub tags: Vec<&'a str>,
+    pub link: Option<Hyperlink<'a>>,
     pub methods: Vec<PlantUMLMethod<'a>>,
+    pub span: Span,
 }
 
-pub fn parse_interface_name(element: &str) -> IResult<&str, &str> {
-    delimited(
-        tag("interface "),
-        take_while(|c: char| !c.is_whitespace()),
-        tag(" {"),
-    )(element)
+impl Spanned for PlantUMLInterface<'_> {
+    fn span(&self) -> Span {
+        self.span
+    }
+
+    fn shift(&mut self, offset: usize) {
+        self.span = self.span.shifted(offset);
+        for method in self.methods.iter_mut() {
+            method.shift(offset);
+        }
+    }
 }
 
-pub fn parse_interface(element: &str) -> IResult<&str, PlantUMLInterface> {
-    let (rest, name) = parse_interface_name(element)?;
-    let (rest, methods) = terminated(many0(terminated(parse_method, char('\n'))), char('}'))(rest)?;
+pub fn parse_interface_name(element: &str) -> IResult<&str, ElementName<'_>> {
+    terminated(parse_element_name("interface"), peek_name_end)(element)
+}
 
-    Ok((rest, PlantUMLInterface { name, methods }))
+pub fn parse_interface(element: &str) -> IResult<&str, PlantUMLInterface<'_>> {
+    parse_interface_with_separator(element, Some(DEFAULT_NAMESPACE_SEPARATOR))
+}
+
+/// Parses an interface whose name is split into namespaces with `separator`, see [`ElementName::qualify`].
+pub fn parse_interface_with_separator<'a>(
+    element: &'a str, separator: Option<&str>,
+) -> IResult<&'a str, PlantUMLInterface<'a>> {
+    fail_on_member_errors(parse_interface_recovering(
+        element,
+        separator,
+        &DEFAULT_DIALECTS,
+    ))
+}
+
+/// Like [`parse_interface_with_separator`], but the methods that can't be parsed are skipped and their errors
+/// returned with the interface.
+pub fn parse_interface_recovering<'a>(
+    element: &'a str, separator: Option<&str>, dialects: &[MemberDialect],
+) -> IResult<&'a str, (PlantUMLInterface<'a>, MemberErrors<'a>)> {
+    let trimmed = element.trim_start();
+    let (rest, name) = parse_interface_name(trimmed)?;
+    let (namespace, name) = name.qualify(separator);
+    let ElementName {
+        name, display_name, ..
+    } = name;
+    let (rest, decorations) = parse_header_decorations(rest)?;
+    let (rest, has_body) = parse_body_opening(rest)?;
+    let mut interface = PlantUMLInterface {
+        name,
+        display_name,
+        namespace,
+        style: decorations.style,
+        stereotypes: decorations.stereotypes,
+        tags: decorations.tags,
+        link: decorations.link,
+        methods: vec![],
+        span: Span::between(element, trimmed, rest),
+    };
+    if !has_body {
+        return Ok((rest, (interface, vec![])));
+    }
+
+    let methods_input = rest;
+    let (rest, (methods, errors)) = parse_body_members(
+        methods_input,
+        terminated(|line| parse_method_in(dialects, line), line_terminator),
+    )?;
+    interface.methods = methods;
+    interface.shift(Span::of(element, methods_input).start);
+    interface.span = Span::between(element, trimmed, rest);
+
+    Ok((rest, (interface, errors)))
 }
 
 #[cfg(test)]
@@ -35,23 +110,74 @@ mod tests {
     use crate::accessibilities::Accessibility;
     use crate::methods::MethodArgument;
     use crate::modifiers::Modifier;
+    use crate::spans::Span;
     #[test]
     fn parse_interface_name_works() {
         let input = "interface ILlanta {\n";
         let (rest, name) = parse_interface_name(input).expect("The name couldn't be parsed!");
-        assert_eq!("ILlanta", name);
-        assert_eq!("\n", rest);
+        assert_eq!(ElementName::new("ILlanta"), name);
+        assert_eq!(" {\n", rest);
     }
 
     #[test]
     fn parse_interface_name_fails() {
         let input = "interface ILlanta asdf {\n";
         let result = parse_interface_name(input);
-        assert_eq!(true, result.is_err());
+        assert!(result.is_err());
 
         let input = "interface ILlanta asdf{\n";
         let result = parse_interface_name(input);
-        assert_eq!(true, result.is_err());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn parse_interface_with_extra_words_fails() {
+        let input = "interface ILlanta asdf {\n";
+        let result = parse_interface(input);
+        assert!(result.is_err());
+
+        let input = "interface ILlanta asdf{\n";
+        let result = parse_interface(input);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn parse_interface_without_body_works() {
+        let (rest, interface) =
+            parse_interface("interface Comparable\n").expect("Coudln't parse the interface!");
+        assert_eq!("\n", rest);
+        assert_eq!(
+            interface,
+            PlantUMLInterface {
+                name: "Comparable",
+                display_name: "Comparable",
+                namespace: vec![],
+                style: None,
+                stereotypes: vec![],
+                tags: vec![],
+                link: None,
+                methods: vec![],
+                span: Span::new(0, 20)
+            }
+        );
+
+        let (rest, interface) =
+            parse_interface("interface Comparable {}").expect("Coudln't parse the interface!");
+        assert_eq!("", rest);
+        assert_eq!(
+            interface,
+            PlantUMLInterface {
+                name: "Comparable",
+                display_name: "Comparable",
+                namespace: vec![],
+                style: None,
+                stereotypes: vec![],
+                tags: vec![],
+                link: None,
+                methods: vec![],
+                span: Span::new(0, 23)
+            }
+        );
     }
 
     #[test]
@@ -68,43 +194,99 @@ mod tests {
             interface,
             PlantUMLInterface {
                 name: "ICarro",
+                display_name: "ICarro",
+                namespace: vec![],
+                style: None,
+                stereotypes: vec![],
+                tags: vec![],
+                link: None,
                 methods: vec![
                     PlantUMLMethod {
                         accessibility: Accessibility::Private,
                         modifier: Modifier::None,
+                        link: None,
                         name: "Avanzar",
                         arguments: vec![],
                         return_type: "void",
+                        span: Span::new(23, 39)
                     },
                     PlantUMLMethod {
                         accessibility: Accessibility::Public,
                         modifier: Modifier::None,
+                        link: None,
                         name: "Serie",
                         arguments: vec![],
                         return_type: "string",
+                        span: Span::new(44, 60)
                     },
                     PlantUMLMethod {
                         accessibility: Accessibility::Protected,
                         modifier: Modifier::None,
+                        link: None,
                         name: "GetEncendido",
                         arguments: vec![],
-                        return_type: "boolean"
+                        return_type: "boolean",
+                        span: Span::new(65, 89)
                     },
                     PlantUMLMethod {
                         accessibility: Accessibility::Private,
                         modifier: Modifier::None,
+                        link: None,
                         name: "SetEncendido",
                         arguments: vec![MethodArgument {
                             name: "encendido",
-                            argument_type: "boolean"
+                            argument_type: "boolean",
+                            span: Span::new(114, 131)
                         }],
-                        return_type: "void"
+                        return_type: "void",
+                        span: Span::new(94, 132)
                     }
-                ]
+                ],
+                span: Span::new(0, 134)
             }
         );
     }
 
+    #[test]
+    fn parse_interface_with_crlf_works() {
+        let input = "interface ICarro {\r\n    - void Avanzar()\r\n}\r\n";
+        let (rest, interface) = parse_interface(input).expect("Coudln't parse the interface!");
+        assert_eq!("\r\n", rest);
+        assert_eq!(
+            interface,
+            PlantUMLInterface {
+                name: "ICarro",
+                display_name: "ICarro",
+                namespace: vec![],
+                style: None,
+                stereotypes: vec![],
+                tags: vec![],
+                link: None,
+                methods: vec![PlantUMLMethod {
+                    accessibility: Accessibility::Private,
+                    modifier: Modifier::None,
+                    link: None,
+                    name: "Avanzar",
+                    arguments: vec![],
+                    return_type: "void",
+                    span: Span::new(24, 40)
+                }],
+                span: Span::new(0, 43)
+            }
+        );
+    }
+
+    #[test]
+    fn parse_interface_with_style_works() {
+        let (_, interface) = parse_interface("interface Comparable #line.bold\n")
+            .expect("Coudln't parse the interface!");
+        assert_eq!(
+            interface.style.and_then(|style| style.line_style),
+            Some(crate::relationships::Stroke::Bold)
+        );
+        assert!(parse_interface("interface Comparable #pink;border:red\n").is_err());
+    }
+
     #[test]
     fn parse_interface_fails() {
         let input = "interface ICarro  assd{
@@ -114,6 +296,6 @@ mod tests {
     - void SetEncendido(boolean encendido)
 }\n";
         let result = parse_interface(input);
-        assert_eq!(true, result.is_err());
+        assert!(result.is_err());
     }
 }
@@ -6,6 +6,24 @@
 //! - interfaces (only parses methods)
 //! - classes (abstract/static classes included)
 //! - enums
+//! - packages and qualified names (`com.acme.Equipo`), and `together` groups
+//! - relationships between elements, association classes and n-ary associations
+//! - notes attached to elements, members or relationships
+//! - hyperlinks and tooltips of elements and members
+//! - titles, legends, layout direction and skinparams of the diagram
+//! - `hide`, `show`, `remove` and `restore` directives, see [`PlantUMLFile::visible_model`]
+//!
+//! Errors know where they were found, have a stable code like `PUML0001` and can be printed like `rustc`
+//! does, in English or Spanish, with [`diagnostics::Renderer`].
+//! [`parse_uml_recovering`] returns them together with everything that could be parsed.
+//! Lines that are ignored are reported as warnings by [`parse_uml_with_warnings`], and [`parse_uml_strict`]
+//! turns them into errors.
+//!
+//! [`parser::Parser`] does the same with [`parser::ParserOptions`]: how strict it is, which member syntaxes
+//! it accepts (see [`dialects::MemberDialect`]), how `!include` lines are expanded, the default namespace
+//! separator and whether comments are kept.
+//! It also reads diagrams from files, readers and whole directories into a [`sources::Source`], which owns
+//! the text the parsed file borrows and knows the file names shown in diagnostics.
 //!
 //! This library doesn't check if the supplied input generates a logical code structure or make type checkings, this means that if you define that a method retuns a Dog it'll take your word for it.
 //!
@@ -25,7 +43,7 @@
 //! TOTAL_TIROS_ESQUINA
 //! TOTAL_TARJETAS_AMARILLAS
 //! TOTAL_TARJETAS_ROJAS
-//! }\n";
+//! }";
 //! let (res, content) = parse_uml_from_contents(input).expect("PlantUML couldn't be parsed!");
 //! assert_eq!("", res);
 //! assert_eq!(content.classes[0].name, "VentanaPrograma")
@@ -33,89 +51,762 @@
 
 pub mod accessibilities;
 pub mod classes;
+pub mod diagnostics;
+pub mod dialects;
+pub mod elements;
 pub mod enums;
+pub mod errors;
 pub mod field;
+pub mod groups;
 pub mod interfaces;
+pub mod links;
+pub mod messages;
+pub mod metadata;
 pub mod methods;
 pub mod modifiers;
+pub mod notes;
+pub mod packages;
+pub mod parser;
+pub mod relationships;
+pub mod sources;
+pub mod spans;
+pub mod styles;
+pub mod suggestions;
+pub mod visibility;
 
-use crate::classes::parse_class;
+use crate::classes::parse_class_recovering;
 use crate::classes::PlantUMLClass;
-use crate::enums::parse_enum;
+use crate::elements::declares_element;
+use crate::elements::parse_comment;
+use crate::elements::starts_with_keyword;
+use crate::elements::ElementName;
+use crate::elements::PlantUMLComment;
+use crate::elements::DEFAULT_NAMESPACE_SEPARATOR;
+use crate::enums::parse_enum_recovering;
 use crate::enums::PlantUMLEnum;
-use crate::field::parse_field;
+use crate::errors::ElementKind;
+use crate::errors::Help;
+use crate::errors::ParseError;
+use crate::errors::ParseErrorKind;
+use crate::errors::ParseWarning;
 use crate::field::PlantUMLField;
-use crate::interfaces::parse_interface;
+use crate::groups::parse_together;
+use crate::groups::PlantUMLGroup;
+use crate::interfaces::parse_interface_recovering;
 use crate::interfaces::PlantUMLInterface;
-use crate::methods::parse_method;
+use crate::links::parse_url_of;
+use crate::links::Hyperlink;
+use crate::metadata::parse_metadata_entry;
+use crate::metadata::PlantUMLMetadata;
 use crate::methods::PlantUMLMethod;
+use crate::notes::parse_note;
+use crate::notes::NoteTarget;
+use crate::notes::PlantUMLNote;
+use crate::packages::parse_namespace_separator;
+use crate::packages::parse_package_with_separator;
+use crate::packages::PlantUMLPackage;
+use crate::parser::ParserOptions;
+use crate::relationships::parse_association_class;
+use crate::relationships::parse_diamond;
+use crate::relationships::parse_relationship;
+use crate::relationships::PlantUMLAssociationClass;
+use crate::relationships::PlantUMLDiamond;
+use crate::relationships::PlantUMLRelationship;
+use crate::spans::Span;
+use crate::spans::Spanned;
+use crate::suggestions::closest;
+use crate::suggestions::KEYWORDS;
+use crate::visibility::parse_directive;
+use crate::visibility::PlantUMLDirective;
 use nom::IResult;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct PlantUMLFile<'a> {
     pub classes: Vec<PlantUMLClass<'a>>,
     pub interfaces: Vec<PlantUMLInterface<'a>>,
     pub enums: Vec<PlantUMLEnum<'a>>,
+    pub packages: Vec<PlantUMLPackage<'a>>,
+    pub groups: Vec<PlantUMLGroup<'a>>,
+    pub relationships: Vec<PlantUMLRelationship<'a>>,
+    pub association_classes: Vec<PlantUMLAssociationClass<'a>>,
+    pub diamonds: Vec<PlantUMLDiamond<'a>>,
+    pub notes: Vec<PlantUMLNote<'a>>,
+    /// `hide`, `show`, `remove` and `restore` directives in the order they were written.
+    pub directives: Vec<PlantUMLDirective<'a>>,
+    pub metadata: PlantUMLMetadata<'a>,
+    /// Separator of qualified names, it's `None` when the diagram disables it with
+    /// `set namespaceSeparator none`.
+    pub namespace_separator: Option<&'a str>,
+    /// Comments of the diagram, it's empty unless [`ParserOptions::retain_trivia`] is set.
+    pub comments: Vec<PlantUMLComment<'a>>,
+}
+
+/// A reference to any of the elements declared in a [`PlantUMLFile`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum PlantUMLElement<'f, 'a> {
+    Class(&'f PlantUMLClass<'a>),
+    Interface(&'f PlantUMLInterface<'a>),
+    Enum(&'f PlantUMLEnum<'a>),
+}
+
+impl<'f, 'a> PlantUMLElement<'f, 'a> {
+    pub fn name(&self) -> &'a str {
+        match self {
+            PlantUMLElement::Class(class) => class.name,
+            PlantUMLElement::Interface(interface) => interface.name,
+            PlantUMLElement::Enum(enu) => enu.name,
+        }
+    }
+
+    pub fn display_name(&self) -> &'a str {
+        match self {
+            PlantUMLElement::Class(class) => class.display_name,
+            PlantUMLElement::Interface(interface) => interface.display_name,
+            PlantUMLElement::Enum(enu) => enu.display_name,
+        }
+    }
+
+    pub fn namespace(&self) -> &'f [&'a str] {
+        match self {
+            PlantUMLElement::Class(class) => &class.namespace,
+            PlantUMLElement::Interface(interface) => &interface.namespace,
+            PlantUMLElement::Enum(enu) => &enu.namespace,
+        }
+    }
+
+    pub fn link(&self) -> Option<Hyperlink<'a>> {
+        match self {
+            PlantUMLElement::Class(class) => class.link,
+            PlantUMLElement::Interface(interface) => interface.link,
+            PlantUMLElement::Enum(enu) => enu.link,
+        }
+    }
+
+    pub fn stereotypes(&self) -> &'f [&'a str] {
+        match self {
+            PlantUMLElement::Class(class) => &class.stereotypes,
+            PlantUMLElement::Interface(interface) => &interface.stereotypes,
+            PlantUMLElement::Enum(enu) => &enu.stereotypes,
+        }
+    }
+
+    pub fn tags(&self) -> &'f [&'a str] {
+        match self {
+            PlantUMLElement::Class(class) => &class.tags,
+            PlantUMLElement::Interface(interface) => &interface.tags,
+            PlantUMLElement::Enum(enu) => &enu.tags,
+        }
+    }
+}
+
+impl<'a> PlantUMLFile<'a> {
+    /// Iterates over every element declared in the file.
+    pub fn elements(&self) -> impl Iterator<Item = PlantUMLElement<'_, 'a>> {
+        self.classes
+            .iter()
+            .map(PlantUMLElement::Class)
+            .chain(self.interfaces.iter().map(PlantUMLElement::Interface))
+            .chain(self.enums.iter().map(PlantUMLElement::Enum))
+    }
+
+    /// Finds the element a reference points to, the reference can be either the name (alias), the
+    /// qualified name or the display name of the element. Names take precedence over display names.
+    pub fn find_element(&self, reference: &str) -> Option<PlantUMLElement<'_, 'a>> {
+        self.elements()
+            .find(|element| element.name() == reference)
+            .or_else(|| {
+                self.elements()
+                    .find(|element| self.is_qualified_name_of(reference, element))
+            })
+            .or_else(|| {
+                self.elements()
+                    .find(|element| element.display_name() == reference)
+            })
+    }
+
+    /// Joins the namespace and the name of an element with the namespace separator of the file.
+    pub fn qualified_name(&self, element: &PlantUMLElement) -> String {
+        let separator = self
+            .namespace_separator
+            .unwrap_or(DEFAULT_NAMESPACE_SEPARATOR);
+        let mut path = element.namespace().to_vec();
+        path.push(element.name());
+        path.join(separator)
+    }
+
+    /// Resolves what the notes are attached to once the whole file is parsed.
+    ///
+    /// References like `Equipo::nombre` that don't point to an element are turned into member targets,
+    /// and the links between floating notes and elements (`N1 .. Equipo`) are moved from the
+    /// relationships into the notes.
+    fn resolve_notes(&mut self) {
+        for i in 0..self.notes.len() {
+            if let NoteTarget::Element(reference) = self.notes[i].target {
+                if self.find_element(reference).is_none() {
+                    if let Some((element, member)) = reference.rsplit_once("::") {
+                        self.notes[i].target = NoteTarget::Member { element, member };
+                    }
+                }
+            }
+        }
+
+        let mut new_indexes = vec![];
+        let mut relationships = vec![];
+        for relationship in self.relationships.drain(..) {
+            let link = self.notes.iter_mut().find_map(|note| match note.alias {
+                Some(alias) if alias == relationship.from => Some((note, relationship.to)),
+                Some(alias) if alias == relationship.to => Some((note, relationship.from)),
+                _ => None,
+            });
+            match link {
+                Some((note, element)) => {
+                    note.links.push(element);
+                    new_indexes.push(None);
+                }
+                None => {
+                    new_indexes.push(Some(relationships.len()));
+                    relationships.push(relationship);
+                }
+            }
+        }
+        self.relationships = relationships;
+
+        for note in self.notes.iter_mut() {
+            if let NoteTarget::Relationship(i) = note.target {
+                note.target = match new_indexes[i] {
+                    Some(i) => NoteTarget::Relationship(i),
+                    None => NoteTarget::Floating,
+                };
+            }
+        }
+    }
+
+    /// Gives the hyperlinks of `url of Foo is [[...]]` to the elements they reference.
+    fn resolve_urls(&mut self, urls: Vec<(&'a str, Hyperlink<'a>)>) {
+        for (reference, link) in urls {
+            match self.find_element(reference) {
+                Some(PlantUMLElement::Class(class)) => {
+                    if let Some(i) = position_of(&self.classes, class) {
+                        self.classes[i].link = Some(link);
+                    }
+                }
+                Some(PlantUMLElement::Interface(interface)) => {
+                    if let Some(i) = position_of(&self.interfaces, interface) {
+                        self.interfaces[i].link = Some(link);
+                    }
+                }
+                Some(PlantUMLElement::Enum(enu)) => {
+                    if let Some(i) = position_of(&self.enums, enu) {
+                        self.enums[i].link = Some(link);
+                    }
+                }
+                None => {}
+            }
+        }
+    }
+
+    /// Declares the interfaces used with the lollipop notation (`Foo ()- IBar`) that the diagram
+    /// doesn't declare with `interface IBar`. They aren't written anywhere, so their span is empty.
+    fn add_implicit_interfaces(&mut self) {
+        for i in 0..self.relationships.len() {
+            let interface = match self.relationships[i].lollipop() {
+                Some((_, interface)) if self.find_element(interface).is_none() => interface,
+                _ => continue,
+            };
+            let (namespace, name) = ElementName::new(interface).qualify(self.namespace_separator);
+            let namespace = place_in_namespace(namespace, &[], &mut self.packages);
+            self.interfaces.push(PlantUMLInterface {
+                name: name.name,
+                display_name: name.display_name,
+                namespace,
+                style: None,
+                stereotypes: vec![],
+                tags: vec![],
+                link: None,
+                methods: vec![],
+                span: Span::default(),
+            });
+        }
+    }
+
+    /// Finds the relationship of every association class and the participants of every diamond.
+    fn resolve_associations(&mut self) {
+        for association in self.association_classes.iter_mut() {
+            let (a, b) = association.ends;
+            association.relationship = self.relationships.iter().position(|relationship| {
+                (relationship.from, relationship.to) == (a, b)
+                    || (relationship.from, relationship.to) == (b, a)
+            });
+        }
+
+        for diamond in self.diamonds.iter_mut() {
+            for relationship in self.relationships.iter() {
+                if relationship.from == diamond.name {
+                    diamond.participants.push(relationship.to);
+                } else if relationship.to == diamond.name {
+                    diamond.participants.push(relationship.from);
+                }
+            }
+        }
+    }
+
+    /// References of relationships that don't match any element but are close to the name of one,
+    /// together with that name. Other undeclared references are elements PlantUML creates implicitly.
+    fn misspelled_references(&self) -> Vec<(&'a str, &'a str)> {
+        let names: Vec<&'a str> = self
+            .elements()
+            .flat_map(|element| [element.name(), element.display_name()])
+            .collect();
+        self.relationships
+            .iter()
+            .flat_map(|relationship| [relationship.from, relationship.to])
+            .filter(|reference| {
+                self.find_element(reference).is_none()
+                    && !self
+                        .diamonds
+                        .iter()
+                        .any(|diamond| diamond.name == *reference)
+            })
+            .filter_map(|reference| Some((reference, closest(reference, names.iter().copied())?)))
+            .collect()
+    }
+
+    fn is_qualified_name_of(&self, reference: &str, element: &PlantUMLElement) -> bool {
+        match self.namespace_separator {
+            Some(separator) if !element.namespace().is_empty() => reference
+                .split(separator)
+                .eq(element.namespace().iter().copied().chain([element.name()])),
+            _ => false,
+        }
+    }
+}
+
+/// Puts a namespace parsed from a qualified name inside the package that is currently open and
+/// registers every package of the resulting namespace that doesn't exist yet.
+fn place_in_namespace<'a>(
+    namespace: Vec<&'a str>, open_packages: &[Vec<&'a str>],
+    packages: &mut Vec<PlantUMLPackage<'a>>,
+) -> Vec<&'a str> {
+    let mut full_namespace = open_packages.last().cloned().unwrap_or_default();
+    full_namespace.extend(namespace);
+
+    for (i, name) in full_namespace.iter().enumerate() {
+        let exists = packages
+            .iter()
+            .any(|package| package.name == *name && package.namespace == full_namespace[..i]);
+        if !exists {
+            packages.push(PlantUMLPackage {
+                name,
+                display_name: name,
+                namespace: full_namespace[..i].to_vec(),
+                style: None,
+            });
+        }
+    }
+    full_namespace
+}
+
+/// A block opened with `{` whose closing brace hasn't been found yet.
+enum OpenBlock {
+    Package,
+    /// Index of the group in [`PlantUMLFile::groups`].
+    Group(usize),
+    /// A package or group whose header couldn't be parsed. Its content is parsed as if it was outside
+    /// of it, the block is only kept to match its closing brace.
+    Unparsed(ElementKind),
+}
+
+impl OpenBlock {
+    /// The kind of element that opened the block.
+    fn element(&self) -> ElementKind {
+        match self {
+            OpenBlock::Package => ElementKind::Package,
+            OpenBlock::Group(_) => ElementKind::Group,
+            OpenBlock::Unparsed(element) => *element,
+        }
+    }
+}
+
+/// Index of the innermost `together` group that is currently open.
+fn innermost_group(open_blocks: &[(OpenBlock, &str)]) -> Option<usize> {
+    open_blocks.iter().rev().find_map(|(block, _)| match block {
+        OpenBlock::Group(i) => Some(*i),
+        OpenBlock::Package | OpenBlock::Unparsed(_) => None,
+    })
+}
+
+/// Adds an element to the innermost `together` group that is currently open.
+fn add_to_group<'a>(
+    name: &'a str, open_blocks: &[(OpenBlock, &str)], groups: &mut [PlantUMLGroup<'a>],
+) {
+    if let Some(i) = innermost_group(open_blocks) {
+        groups[i].elements.push(name);
+    }
+}
+
+/// Position of an element in the list it's stored in.
+fn position_of<T>(elements: &[T], element: &T) -> Option<usize> {
+    elements
+        .iter()
+        .position(|other| std::ptr::eq(other, element))
+}
+
+/// Checks if a line opens a block that it doesn't close.
+fn opens_block(line: &str) -> bool {
+    line.matches('{').count() > line.matches('}').count()
+}
+
+/// Skips an element that couldn't be parsed. Packages and groups only skip their header, their
+/// content is made of other elements. Notes whose text isn't on their first line skip every line
+/// until `end note`. The rest skip their first line and, when it opens a body, every line until the
+/// brace that closes it. The declaration of another element ends the body or the note too, so a
+/// missing closing brace doesn't hide the rest of the diagram.
+fn skip_element(content: &str, element: ElementKind) -> &str {
+    let mut lines = content.split_inclusive('\n');
+    let first = lines.next().unwrap_or_default();
+    let mut rest = &content[first.len()..];
+    if matches!(element, ElementKind::Package | ElementKind::Group) {
+        return rest;
+    }
+    if element == ElementKind::Note {
+        if first.contains([':', '"']) {
+            return rest;
+        }
+        for line in lines {
+            if declares_element(line.trim_start()) {
+                return rest;
+            }
+            rest = &rest[line.len()..];
+            if line.trim().strip_prefix("end").map(str::trim_start) == Some("note") {
+                return rest;
+            }
+        }
+        return rest;
+    }
+
+    let mut depth = first.matches('{').count() as isize - first.matches('}').count() as isize;
+    if depth <= 0 && !rest.trim_start().starts_with('{') {
+        return rest;
+    }
+    for line in lines {
+        if declares_element(line.trim_start()) {
+            return rest;
+        }
+        rest = &rest[line.len()..];
+        depth += line.matches('{').count() as isize - line.matches('}').count() as isize;
+        if depth <= 0 {
+            return rest;
+        }
+    }
+    rest
 }
 
 fn try_parse_element<'a, OK, P: Fn(&'a str) -> IResult<&'a str, OK>>(
-    content: &'a str, ok_array: &mut Vec<OK>,
-    err_array: &mut Vec<nom::Err<nom::error::Error<&'a str>>>, parser: P,
-) -> &'a str {
+    content: &'a str, element: ElementKind,
+    err_array: &mut Vec<(ElementKind, nom::Err<nom::error::Error<&'a str>>)>, parser: P,
+) -> (&'a str, Option<OK>) {
     match parser(content) {
-        Ok((rest, elm)) => {
-            ok_array.push(elm);
-            rest
-        }
+        Ok((rest, elm)) => (rest, Some(elm)),
         Err(e) => {
-            err_array.push(e);
-            match content.find('}') {
-                Some(i) => &content[i..],
-                None => content,
-            }
+            err_array.push((element, e));
+            (skip_element(content, element), None)
         }
     }
 }
 
-//TODO: This should return a custom error instead of a Vec<String>
-pub fn parse_uml_from_contents(content: &str) -> Result<(&str, PlantUMLFile), Vec<String>> {
+/// Parses a class diagram, every element that can't be parsed is reported as a [`ParseError`] whose
+/// position is relative to `content`. Lines that are ignored are reported by
+/// [`parse_uml_with_warnings`].
+///
+/// Members are read in [`dialects::MemberDialect::Mixed`], use a [`parser::Parser`] to accept other
+/// dialects too.
+pub fn parse_uml_from_contents(
+    content: &str,
+) -> Result<(&str, PlantUMLFile<'_>), Vec<ParseError<'_>>> {
+    let (rest, file, _) = parse_uml_with_warnings(content)?;
+    Ok((rest, file))
+}
+
+/// Parses a class diagram like [`parse_uml_from_contents`], and also returns a [`ParseWarning`] for
+/// every line that was ignored because it isn't a supported construct.
+pub fn parse_uml_with_warnings(
+    content: &str,
+) -> Result<(&str, PlantUMLFile<'_>, Vec<ParseWarning<'_>>), Vec<ParseError<'_>>> {
+    let (rest, file, errors, warnings) = parse_contents(content, &ParserOptions::default());
+    if errors.is_empty() {
+        Ok((rest, file, warnings))
+    } else {
+        Err(errors)
+    }
+}
+
+/// Parses a class diagram like [`parse_uml_from_contents`], but every warning is an error too, so
+/// nothing in the diagram can be ignored. References to undeclared elements are valid PlantUML, so
+/// they aren't errors.
+pub fn parse_uml_strict(content: &str) -> Result<(&str, PlantUMLFile<'_>), Vec<ParseError<'_>>> {
+    let (rest, file, mut errors, warnings) = parse_contents(content, &ParserOptions::default());
+    errors.extend(
+        warnings
+            .into_iter()
+            .filter(|warning| warning.kind.is_strict_error()),
+    );
+    errors.sort_by_key(|error| error.span.start);
+    if errors.is_empty() {
+        Ok((rest, file))
+    } else {
+        Err(errors)
+    }
+}
+
+/// Parses a class diagram like [`parse_uml_with_warnings`], but the errors don't discard what was
+/// parsed. Members and elements that can't be parsed are left out of the returned file, everything
+/// else is kept, which is useful to work with a diagram that is still being written.
+pub fn parse_uml_recovering(
+    content: &str,
+) -> (PlantUMLFile<'_>, Vec<ParseError<'_>>, Vec<ParseWarning<'_>>) {
+    let (_, file, errors, warnings) = parse_contents(content, &ParserOptions::default());
+    (file, errors, warnings)
+}
+
+/// Parses a class diagram, the returned errors and warnings aren't sorted together.
+#[allow(clippy::type_complexity)]
+pub(crate) fn parse_contents<'a>(
+    content: &'a str, options: &ParserOptions,
+) -> (
+    &'a str,
+    PlantUMLFile<'a>,
+    Vec<ParseError<'a>>,
+    Vec<ParseWarning<'a>>,
+) {
+    let source = content;
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
     let mut trimmed = content.trim_start();
     let mut classes = vec![];
     let mut interfaces = vec![];
     let mut enums = vec![];
+    let mut packages = vec![];
+    let mut relationships = vec![];
+    let mut association_classes = vec![];
+    let mut diamonds = vec![];
+    let mut notes = vec![];
+    let mut directives = vec![];
+    let mut urls = vec![];
+    let mut metadata = PlantUMLMetadata::default();
     let mut errors = vec![];
-    loop {
-        match trimmed.find('\n') {
-            None => break,
-            Some(i) => {
-                let line = &trimmed[..i];
-                if line.starts_with("class") {
-                    trimmed = try_parse_element(trimmed, &mut classes, &mut errors, parse_class);
-                } else if line.starts_with("interface") {
-                    trimmed =
-                        try_parse_element(trimmed, &mut interfaces, &mut errors, parse_interface);
-                } else if line.starts_with("enum") {
-                    trimmed = try_parse_element(trimmed, &mut enums, &mut errors, parse_enum);
-                } else {
-                    trimmed = &trimmed[i..];
+    let mut warnings = vec![];
+    let mut namespace_separator = options.namespace_separator;
+    let mut comments = vec![];
+    let mut groups = vec![];
+    let mut open_packages: Vec<Vec<&str>> = vec![];
+    let mut open_blocks = vec![];
+    while !trimmed.is_empty() {
+        let line = match trimmed.find('\n') {
+            Some(i) => &trimmed[..i],
+            None => trimmed,
+        };
+        if let Ok((rest, text)) = parse_comment(trimmed) {
+            if options.retain_trivia {
+                comments.push(PlantUMLComment {
+                    text,
+                    span: Span::between(source, trimmed, rest),
+                });
+            }
+            trimmed = rest;
+        } else if line.starts_with("@start") || line.starts_with("@end") {
+            trimmed = &trimmed[line.len()..];
+        } else if starts_with_keyword(line, "class") {
+            let (rest, class) =
+                try_parse_element(trimmed, ElementKind::Class, &mut errors, |element| {
+                    parse_class_recovering(element, namespace_separator, &options.dialects)
+                });
+            if let Some((mut class, member_errors)) = class {
+                errors.extend(member_errors.into_iter().map(|e| (ElementKind::Class, e)));
+                class.shift(Span::of(source, trimmed).start);
+                class.namespace =
+                    place_in_namespace(class.namespace, &open_packages, &mut packages);
+                add_to_group(class.name, &open_blocks, &mut groups);
+                classes.push(class);
+            }
+            trimmed = rest;
+        } else if starts_with_keyword(line, "interface") {
+            let (rest, interface) =
+                try_parse_element(trimmed, ElementKind::Interface, &mut errors, |element| {
+                    parse_interface_recovering(element, namespace_separator, &options.dialects)
+                });
+            if let Some((mut interface, member_errors)) = interface {
+                errors.extend(
+                    member_errors
+                        .into_iter()
+                        .map(|e| (ElementKind::Interface, e)),
+                );
+                interface.shift(Span::of(source, trimmed).start);
+                interface.namespace =
+                    place_in_namespace(interface.namespace, &open_packages, &mut packages);
+                add_to_group(interface.name, &open_blocks, &mut groups);
+                interfaces.push(interface);
+            }
+            trimmed = rest;
+        } else if starts_with_keyword(line, "enum") {
+            let (rest, enu) =
+                try_parse_element(trimmed, ElementKind::Enum, &mut errors, |element| {
+                    parse_enum_recovering(element, namespace_separator)
+                });
+            if let Some((mut enu, member_errors)) = enu {
+                errors.extend(member_errors.into_iter().map(|e| (ElementKind::Enum, e)));
+                enu.shift(Span::of(source, trimmed).start);
+                enu.namespace = place_in_namespace(enu.namespace, &open_packages, &mut packages);
+                add_to_group(enu.name, &open_blocks, &mut groups);
+                enums.push(enu);
+            }
+            trimmed = rest;
+        } else if starts_with_keyword(line, "package") || starts_with_keyword(line, "namespace") {
+            let (rest, package) =
+                try_parse_element(trimmed, ElementKind::Package, &mut errors, |element| {
+                    parse_package_with_separator(element, namespace_separator)
+                });
+            if let Some((package, has_body)) = package {
+                let mut path = package.namespace;
+                path.push(package.name);
+                let path = place_in_namespace(path, &open_packages, &mut packages);
+                if let Some(declared) = packages.iter_mut().find(|p| p.path() == path) {
+                    declared.display_name = package.display_name;
+                    declared.style = package.style;
+                }
+                if has_body {
+                    open_packages.push(path);
+                    open_blocks.push((OpenBlock::Package, line.trim_end()));
                 }
-                trimmed = trimmed.trim_start();
+            } else if opens_block(line) {
+                open_blocks.push((OpenBlock::Unparsed(ElementKind::Package), line.trim_end()));
             }
-        };
+            trimmed = rest;
+        } else if starts_with_keyword(line, "note") {
+            let last_relationship = relationships.len().checked_sub(1);
+            let (rest, note) =
+                try_parse_element(trimmed, ElementKind::Note, &mut errors, |element| {
+                    parse_note(element, last_relationship)
+                });
+            notes.extend(note);
+            trimmed = rest;
+        } else if line.starts_with("<>") || starts_with_keyword(line, "diamond") {
+            let (rest, diamond) =
+                try_parse_element(trimmed, ElementKind::Diamond, &mut errors, parse_diamond);
+            diamonds.extend(diamond);
+            trimmed = rest;
+        } else if let Ok((rest, association)) = parse_association_class(trimmed) {
+            association_classes.push(association);
+            trimmed = rest;
+        } else if let Ok((rest, directive)) = parse_directive(trimmed) {
+            directives.push(directive);
+            trimmed = rest;
+        } else if let Ok((rest, url)) = parse_url_of(trimmed) {
+            urls.push(url);
+            trimmed = rest;
+        } else if let Ok((rest, entry)) = parse_metadata_entry(trimmed) {
+            metadata.insert(entry);
+            trimmed = rest;
+        } else if let Ok((rest, separator)) = parse_namespace_separator(trimmed) {
+            namespace_separator = separator;
+            trimmed = rest;
+        } else if starts_with_keyword(line, "together") || line.starts_with("together{") {
+            let parent = innermost_group(&open_blocks);
+            let (rest, group) =
+                try_parse_element(trimmed, ElementKind::Group, &mut errors, |element| {
+                    parse_together(element, parent)
+                });
+            if let Some((group, has_body)) = group {
+                if has_body {
+                    open_blocks.push((OpenBlock::Group(groups.len()), line.trim_end()));
+                }
+                groups.push(group);
+            } else if opens_block(line) {
+                open_blocks.push((OpenBlock::Unparsed(ElementKind::Group), line.trim_end()));
+            }
+            trimmed = rest;
+        } else if line.trim_end() == "}" && !open_blocks.is_empty() {
+            if let Some((OpenBlock::Package, _)) = open_blocks.pop() {
+                open_packages.pop();
+            }
+            trimmed = &trimmed[line.len()..];
+        } else if let Ok((rest, relationship)) = parse_relationship(trimmed) {
+            relationships.push(relationship);
+            trimmed = rest;
+        } else {
+            let kind = if line.trim_end() == "}" {
+                ParseErrorKind::UnmatchedBrace
+            } else if line.starts_with('!') {
+                ParseErrorKind::UnsupportedPreprocessor
+            } else {
+                ParseErrorKind::UnrecognizedLine
+            };
+            let text = line.trim_end();
+            let help = match kind {
+                ParseErrorKind::UnrecognizedLine => {
+                    let word = text.split(|c: char| c.is_whitespace() || c == '{').next();
+                    closest(word.unwrap_or_default(), KEYWORDS).map(Help::DidYouMean)
+                }
+                _ => None,
+            };
+            warnings.push(ParseWarning::new(
+                source,
+                kind,
+                ElementKind::Diagram,
+                text,
+                help,
+            ));
+            trimmed = if kind == ParseErrorKind::UnrecognizedLine && opens_block(line) {
+                skip_element(trimmed, ElementKind::Diagram)
+            } else {
+                &trimmed[line.len()..]
+            };
+        }
+        trimmed = trimmed.trim_start();
     }
 
-    if errors.len() != 0 {
-        Err(errors.iter().map(|e| format!("{:?}", e)).collect())
-    } else {
-        Ok((
-            trimmed,
-            PlantUMLFile {
-                classes,
-                interfaces,
-                enums,
-            },
-        ))
+    let mut file = PlantUMLFile {
+        classes,
+        interfaces,
+        enums,
+        packages,
+        groups,
+        relationships,
+        association_classes,
+        diamonds,
+        notes,
+        directives,
+        metadata,
+        namespace_separator,
+        comments,
+    };
+    file.resolve_notes();
+    file.resolve_urls(urls);
+    file.resolve_associations();
+    file.add_implicit_interfaces();
+    for (reference, name) in file.misspelled_references() {
+        warnings.push(ParseWarning::new(
+            source,
+            ParseErrorKind::UnresolvedReference,
+            ElementKind::Relationship,
+            reference,
+            Some(Help::DidYouMean(name)),
+        ));
     }
+    warnings.sort_by_key(|warning| warning.span.start);
+    let mut errors: Vec<_> = errors
+        .into_iter()
+        .map(|(element, e)| ParseError::from_nom(source, element, e))
+        .collect();
+    for (block, header) in open_blocks {
+        errors.push(ParseError::new(
+            source,
+            ParseErrorKind::MissingBrace,
+            block.element(),
+            header,
+            Some(Help::CloseBody),
+        ));
+    }
+    errors.sort_by_key(|error| error.span.start);
+    (trimmed, file, errors, warnings)
 }
 
 #[cfg(test)]
@@ -123,8 +814,13 @@ mod tests {
     use super::*;
     use crate::accessibilities::Accessibility;
     use crate::enums::PlantUMLEnumVariant;
+    use crate::errors::Help;
+    use crate::errors::ParseErrorKind;
     use crate::methods::MethodArgument;
     use crate::modifiers::Modifier;
+    use crate::notes::NotePosition;
+    use crate::spans::Span;
+    use crate::styles::Background;
     use crate::PlantUMLClass;
 
     #[test]
@@ -143,8 +839,758 @@ enum AccionUsuario {
     TOTAL_TARJETAS_AMARILLAS
     TOTAL_TARJETAS_ROJAS
 }\n";
-        let res = parse_uml_from_contents(input);
-        assert_eq!(true, res.is_err());
+        let errors = parse_uml_from_contents(input).expect_err("PlantUML shouldn't be parsed!");
+        assert_eq!(
+            errors,
+            vec![
+                ParseError {
+                    kind: ParseErrorKind::UnexpectedToken,
+                    element: ElementKind::Class,
+                    text: "Programa {",
+                    span: Span::new(14, 24),
+                    line: 1,
+                    column: 15,
+                    help: None,
+                },
+                ParseError {
+                    kind: ParseErrorKind::InvalidIdentifier,
+                    element: ElementKind::Enum,
+                    text: "TOTAL_ GOLES",
+                    span: Span::new(220, 232),
+                    line: 10,
+                    column: 5,
+                    help: Some(Help::VariantNameWithSpaces),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_content_with_flexible_headers_works() {
+        let input = "class  Jugador{\n}\ninterface\tComparable \n{\n}\nenum Color{}  \n";
+        let (res, content) = parse_uml_from_contents(input).expect("PlantUML couldn't be parsed!");
+        assert_eq!("", res);
+        assert_eq!(content.classes[0].name, "Jugador");
+        assert_eq!(content.interfaces[0].name, "Comparable");
+        assert_eq!(content.enums[0].name, "Color");
+    }
+
+    #[test]
+    fn parse_content_with_crlf_works() {
+        let input = "class VentanaPrograma {\r\n    - analizador: AnalizadorEquipos\r\n    + {ctor} VentanaPrograma()\r\n}\r\n\r\nenum AccionUsuario {\r\n    SALIR\r\n    TOTAL_GOLES\r\n}\r\n";
+        let (res, content) = parse_uml_from_contents(input).expect("PlantUML couldn't be parsed!");
+        assert_eq!("", res);
+        assert_eq!(
+            content.classes[0].fields[0],
+            PlantUMLField {
+                name: "analizador",
+                accessibility: Accessibility::Private,
+                field_type: "AnalizadorEquipos",
+                modifier: Modifier::None,
+                link: None,
+                span: Span::new(29, 60)
+            }
+        );
+        assert_eq!(content.classes[0].methods[0].name, "VentanaPrograma");
+        assert_eq!(
+            content.enums[0].variants,
+            vec![
+                PlantUMLEnumVariant {
+                    name: "SALIR",
+                    span: Span::new(125, 130)
+                },
+                PlantUMLEnumVariant {
+                    name: "TOTAL_GOLES",
+                    span: Span::new(136, 147)
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_content_with_bom_works() {
+        let input = "\u{feff}class Jugador {\n    - nombre: String\n}\n";
+        let (res, content) = parse_uml_from_contents(input).expect("PlantUML couldn't be parsed!");
+        assert_eq!("", res);
+        assert_eq!(content.classes[0].name, "Jugador");
+        assert_eq!(content.classes[0].fields[0].name, "nombre");
+    }
+
+    #[test]
+    fn parse_content_without_trailing_newline_works() {
+        let input = "class Jugador {\n    - nombre: String\n}\nenum Color {\n    ROJO\n    AZUL\n}";
+        let (res, content) = parse_uml_from_contents(input).expect("PlantUML couldn't be parsed!");
+        assert_eq!("", res);
+        assert_eq!(content.classes[0].fields[0].name, "nombre");
+        assert_eq!(
+            content.enums[0].variants,
+            vec![
+                PlantUMLEnumVariant {
+                    name: "ROJO",
+                    span: Span::new(56, 60)
+                },
+                PlantUMLEnumVariant {
+                    name: "AZUL",
+                    span: Span::new(65, 69)
+                }
+            ]
+        );
+
+        let (_, content) =
+            parse_uml_from_contents("class Jugador").expect("PlantUML couldn't be parsed!");
+        assert_eq!(content.classes[0].name, "Jugador");
+    }
+
+    #[test]
+    fn parse_content_with_unterminated_element_fails() {
+        let errors = parse_uml_from_contents("class Jugador {\n    - nombre: String")
+            .expect_err("PlantUML shouldn't be parsed!");
+        assert_eq!(errors[0].kind, ParseErrorKind::MissingBrace);
+        assert_eq!(
+            errors[0].to_string(),
+            "2:21: missing closing brace while parsing the class"
+        );
+    }
+
+    #[test]
+    fn parse_content_with_warnings_works() {
+        let input = "@startuml
+' Modelo de equipos
+/' Los jugadores
+   pertenecen a un equipo '/
+clas Equipo {
+}
+!include estilos.iuml
+class Jugador
+@enduml
+";
+        let (_, content, warnings) =
+            parse_uml_with_warnings(input).expect("PlantUML couldn't be parsed!");
+        assert_eq!(content.classes.len(), 1);
+        let warnings: Vec<_> = warnings
+            .iter()
+            .map(|warning| (warning.kind, warning.text, warning.line, warning.column))
+            .collect();
+        assert_eq!(
+            warnings,
+            vec![
+                (ParseErrorKind::UnrecognizedLine, "clas Equipo {", 5, 1),
+                (
+                    ParseErrorKind::UnsupportedPreprocessor,
+                    "!include estilos.iuml",
+                    7,
+                    1
+                ),
+            ]
+        );
+        assert!(parse_uml_from_contents(input).is_ok());
+    }
+
+    #[test]
+    fn parse_content_suggests_keywords_and_names() {
+        let input = "clas Equipo {
+}
+interfase Comparable
+enumm Color
+class Jugador
+class Partido
+Jugadr --> Partido
+Equipo --> Arbitro
+";
+        let (_, _, warnings) =
+            parse_uml_with_warnings(input).expect("PlantUML couldn't be parsed!");
+        let warnings: Vec<_> = warnings
+            .iter()
+            .map(|warning| (warning.kind, warning.text, warning.help))
+            .collect();
+        assert_eq!(
+            warnings,
+            vec![
+                (
+                    ParseErrorKind::UnrecognizedLine,
+                    "clas Equipo {",
+                    Some(Help::DidYouMean("class"))
+                ),
+                (
+                    ParseErrorKind::UnrecognizedLine,
+                    "interfase Comparable",
+                    Some(Help::DidYouMean("interface"))
+                ),
+                (
+                    ParseErrorKind::UnrecognizedLine,
+                    "enumm Color",
+                    Some(Help::DidYouMean("enum"))
+                ),
+                (
+                    ParseErrorKind::UnresolvedReference,
+                    "Jugadr",
+                    Some(Help::DidYouMean("Jugador"))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_recovering_reports_each_mistake_once() {
+        let (content, errors, warnings) =
+            parse_uml_recovering("clas Foo {\n - a: int\n}\nclass Equipo\n");
+        assert!(errors.is_empty());
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].text, "clas Foo {");
+        assert_eq!(content.classes[0].name, "Equipo");
+
+        let input = "note left Equipo\n  sin destino\n  en dos líneas\nend note\nclass Equipo\n";
+        let (content, errors, warnings) = parse_uml_recovering(input);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].element, ElementKind::Note);
+        assert!(warnings.is_empty());
+        assert_eq!(content.classes[0].name, "Equipo");
+
+        let (content, errors, warnings) = parse_uml_recovering("enum E {\n A\n B}\nclass C\n");
+        assert!(errors.is_empty());
+        assert!(warnings.is_empty());
+        assert_eq!(content.enums[0].variants.len(), 2);
+        assert_eq!(content.classes[0].name, "C");
+
+        let input = "note left of Equipo\n  sin final\nclass Equipo\n";
+        let (content, errors, warnings) = parse_uml_recovering(input);
+        assert_eq!(errors.len(), 1);
+        assert!(warnings.is_empty());
+        assert_eq!(content.classes[0].name, "Equipo");
+    }
+
+    #[test]
+    fn parse_strict_fails_on_warnings() {
+        let input = "clas Equipo {\n}\n}\nclass Ventana Programa\nenun Color\n";
+        let errors = parse_uml_strict(input).expect_err("PlantUML shouldn't be parsed!");
+        let errors: Vec<_> = errors
+            .iter()
+            .map(|error| (error.kind, error.element, error.line))
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                (ParseErrorKind::UnrecognizedLine, ElementKind::Diagram, 1),
+                (ParseErrorKind::UnmatchedBrace, ElementKind::Diagram, 3),
+                (ParseErrorKind::UnexpectedToken, ElementKind::Class, 4),
+                (ParseErrorKind::UnrecognizedLine, ElementKind::Diagram, 5),
+            ]
+        );
+
+        let input = "@startuml\n' Equipo\nclass Equipo\n@enduml\n";
+        let (_, content) = parse_uml_strict(input).expect("PlantUML couldn't be parsed!");
+        assert_eq!(content.classes[0].name, "Equipo");
+    }
+
+    #[test]
+    fn parse_strict_with_undeclared_references_works() {
+        let input = "class A\nclass B\nA --> C\n";
+        let (_, content) = parse_uml_strict(input).expect("PlantUML couldn't be parsed!");
+        assert_eq!(content.relationships[0].to, "C");
+        let (_, _, warnings) =
+            parse_uml_with_warnings(input).expect("PlantUML couldn't be parsed!");
+        assert!(warnings.is_empty());
+
+        let input = "class Jugador\nJugadr --> Jugador\n";
+        assert!(parse_uml_strict(input).is_ok());
+    }
+
+    #[test]
+    fn parse_recovering_keeps_what_can_be_parsed() {
+        let input = "package modelo {
+  class Equipo {
+    - nombre Equipo: String
+    - puntos: int
+  }
+  class Ventana Programa {
+    + void abrir()
+  }
+  package mal nombre {
+    enum Color {
+      ROJO
+      VERDE CLARO
+    }
+  }
+  class Jugador {
+    - dorsal: int
+  class Partido
+}
+Equipo --> Jugador
+";
+        let (content, errors, _) = parse_uml_recovering(input);
+        let classes: Vec<_> = content
+            .classes
+            .iter()
+            .map(|class| (class.name, class.namespace.clone(), class.fields.len()))
+            .collect();
+        assert_eq!(
+            classes,
+            vec![
+                ("Equipo", vec!["modelo"], 1),
+                ("Jugador", vec!["modelo"], 1),
+                ("Partido", vec!["modelo"], 0),
+            ]
+        );
+        assert_eq!(content.enums[0].namespace, vec!["modelo"]);
+        assert_eq!(content.enums[0].variants[0].name, "ROJO");
+        assert_eq!(content.enums[0].variants.len(), 1);
+        assert_eq!(content.relationships.len(), 1);
+
+        let errors: Vec<_> = errors
+            .iter()
+            .map(|error| (error.kind, error.line, error.column))
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                (ParseErrorKind::InvalidIdentifier, 3, 7),
+                (ParseErrorKind::UnexpectedToken, 6, 17),
+                (ParseErrorKind::UnexpectedToken, 9, 15),
+                (ParseErrorKind::InvalidIdentifier, 12, 7),
+                (ParseErrorKind::MissingBrace, 17, 3),
+            ]
+        );
+        assert!(parse_uml_from_contents(input).is_err());
+    }
+
+    #[test]
+    fn parse_recovering_without_errors_works() {
+        let input = "class Equipo {\n  - nombre: String\n}\n";
+        let (content, errors, _) = parse_uml_recovering(input);
+        assert!(errors.is_empty());
+        assert_eq!(Ok(("", content)), parse_uml_from_contents(input));
+    }
+
+    #[test]
+    fn parse_recovering_with_non_ascii_members_works() {
+        let input = "class Jugador {
+  - año: int
+  + void jugar(ñ)
+  + void correr(String ritmo
+  + void parar()
+}
+interface Árbitro {
+  + void pitar(ñandú
+}
+enum Posición {
+  DEFENSA
+}
+";
+        let (content, errors, _) = parse_uml_recovering(input);
+        let errors: Vec<_> = errors.iter().map(|error| error.text).collect();
+        assert_eq!(
+            errors,
+            vec![
+                "+ void jugar(ñ)",
+                "+ void correr(String ritmo",
+                "+ void pitar(ñandú"
+            ]
+        );
+        assert_eq!(content.classes[0].fields[0].name, "año");
+        let methods: Vec<_> = content.classes[0]
+            .methods
+            .iter()
+            .map(|method| method.name)
+            .collect();
+        assert_eq!(methods, vec!["parar"]);
+        assert_eq!(content.interfaces[0].name, "Árbitro");
+        assert_eq!(content.enums[0].name, "Posición");
+    }
+
+    #[test]
+    fn parse_content_with_unknown_modifier_fails() {
+        let errors = parse_uml_from_contents("class Jugador {\n    + {final} void jugar()\n}\n")
+            .expect_err("PlantUML shouldn't be parsed!");
+        assert_eq!(errors[0].kind, ParseErrorKind::UnknownModifier);
+        assert_eq!(errors[0].text, "+ {final} void jugar()");
+    }
+
+    #[test]
+    fn parse_content_with_the_default_dialect_works() {
+        let errors = parse_uml_from_contents("class Equipo {\n  - nombre String\n}\n")
+            .expect_err("PlantUML shouldn't be parsed!");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, ParseErrorKind::UnexpectedToken);
+        assert_eq!(errors[0].text, "- nombre String");
+        assert_eq!(errors[0].span, Span::new(17, 32));
+
+        let input = "class Jugador {
+    - String nombre
+    - edad: int
+    + void jugar(int minutos)
+    + correr(metros: int): double
+}
+";
+        let errors = parse_uml_from_contents(input).expect_err("PlantUML shouldn't be parsed!");
+        let errors: Vec<_> = errors.iter().map(|error| error.text).collect();
+        assert_eq!(errors, ["- String nombre", "+ correr(metros: int): double"]);
+    }
+
+    #[test]
+    fn parse_content_with_every_dialect_works() {
+        let input = "class Jugador {
+    - String nombre
+    - edad: int
+    + void jugar(int minutos)
+    + correr(metros: int): double
+}
+";
+        let parser = parser::Parser::new(parser::ParserOptions {
+            dialects: dialects::ALL_DIALECTS.to_vec(),
+            ..parser::ParserOptions::default()
+        });
+        let output = parser.parse_str(input);
+        assert!(output.errors.is_empty());
+        let class = &output.file.classes[0];
+        let fields: Vec<_> = class
+            .fields
+            .iter()
+            .map(|field| (field.name, field.field_type))
+            .collect();
+        assert_eq!(fields, vec![("nombre", "String"), ("edad", "int")]);
+        let methods: Vec<_> = class
+            .methods
+            .iter()
+            .map(|method| (method.name, method.return_type))
+            .collect();
+        assert_eq!(methods, vec![("jugar", "void"), ("correr", "double")]);
+    }
+
+    #[test]
+    fn parse_content_with_member_kind_modifiers_works() {
+        let input = "class Jugador {
+    + {classifier} total: int
+    - {field} nombre: String
+    + {method} void jugar()
+}
+";
+        let (_, content) = parse_uml_from_contents(input).expect("PlantUML couldn't be parsed!");
+        let class = &content.classes[0];
+        assert_eq!(class.fields[0].modifier, Modifier::Static);
+        assert_eq!(class.fields[1].modifier, Modifier::None);
+        assert_eq!(class.methods[0].name, "jugar");
+    }
+
+    #[test]
+    fn parse_content_with_aliases_works() {
+        let input = "class \"Cuenta Bancaria\" as CB {
+    - saldo: double
+}
+class Cliente as \"Cliente del Banco\"
+\"Cliente del Banco\" \"1\" --> \"*\" CB : posee
+";
+        let (_, content) = parse_uml_from_contents(input).expect("PlantUML couldn't be parsed!");
+        assert_eq!(content.classes[0].name, "CB");
+        assert_eq!(content.classes[0].display_name, "Cuenta Bancaria");
+        assert_eq!(content.classes[1].name, "Cliente");
+        assert_eq!(content.classes[1].display_name, "Cliente del Banco");
+
+        let relationship = &content.relationships[0];
+        assert_eq!(
+            content.find_element(relationship.from),
+            Some(PlantUMLElement::Class(&content.classes[1]))
+        );
+        assert_eq!(
+            content.find_element(relationship.to),
+            Some(PlantUMLElement::Class(&content.classes[0]))
+        );
+        assert_eq!(
+            content.find_element("Cuenta Bancaria").map(|e| e.name()),
+            Some("CB")
+        );
+        assert_eq!(content.find_element("Banco"), None);
+    }
+
+    #[test]
+    fn parse_content_with_qualified_names_works() {
+        let input = "class com.acme.Equipo
+package com.acme {
+    class Jugador
+    package modelo {
+        enum Color
+    }
+}
+interface Outer.Inner
+set namespaceSeparator ::
+class a::b::C
+com.acme.Equipo --> a::b::C
+set namespaceSeparator none
+class x.y.Z
+";
+        let (_, content) = parse_uml_from_contents(input).expect("PlantUML couldn't be parsed!");
+        let names: Vec<(&[&str], &str)> = content
+            .elements()
+            .map(|e| (e.namespace(), e.name()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                (&["com", "acme"][..], "Equipo"),
+                (&["com", "acme"][..], "Jugador"),
+                (&["a", "b"][..], "C"),
+                (&[][..], "x.y.Z"),
+                (&["Outer"][..], "Inner"),
+                (&["com", "acme", "modelo"][..], "Color"),
+            ]
+        );
+
+        let packages: Vec<Vec<&str>> = content.packages.iter().map(|p| p.path()).collect();
+        assert_eq!(
+            packages,
+            vec![
+                vec!["com"],
+                vec!["com", "acme"],
+                vec!["com", "acme", "modelo"],
+                vec!["Outer"],
+                vec!["a"],
+                vec!["a", "b"],
+            ]
+        );
+        assert_eq!(content.namespace_separator, None);
+    }
+
+    #[test]
+    fn parse_content_with_styles_works() {
+        let input = "package modelo #DDDDDD {
+    class Equipo #pink;line:red {
+        - nombre: String
+    }
+}
+";
+        let (_, content) = parse_uml_from_contents(input).expect("PlantUML couldn't be parsed!");
+        assert_eq!(
+            content.packages[0].style.and_then(|style| style.background),
+            Some(Background::Color("DDDDDD"))
+        );
+        let style = content.classes[0]
+            .style
+            .expect("The class should have a style");
+        assert_eq!(style.background, Some(Background::Color("pink")));
+        assert_eq!(style.line_color, Some("red"));
+    }
+
+    #[test]
+    fn find_element_by_qualified_name_works() {
+        let input = "set namespaceSeparator ::
+class a::b::C
+a::b::C --> C
+";
+        let (_, content) = parse_uml_from_contents(input).expect("PlantUML couldn't be parsed!");
+        let class = PlantUMLElement::Class(&content.classes[0]);
+        assert_eq!(content.find_element("a::b::C"), Some(class));
+        assert_eq!(content.find_element("C"), Some(class));
+        assert_eq!(content.find_element("a::C"), None);
+        assert_eq!(content.qualified_name(&class), "a::b::C");
+        assert_eq!(content.relationships[0].from, "a::b::C");
+    }
+
+    #[test]
+    fn parse_content_with_notes_works() {
+        let input = "class Equipo {
+    - nombre: String
+}
+class Jugador
+note left of Equipo : máximo 11 jugadores
+note right of Equipo::nombre
+    único en el torneo
+end note
+note \"Regla de negocio\" as N1
+N1 .. Equipo
+Equipo *-- Jugador
+note on link : un jugador pertenece a un solo equipo
+";
+        let (_, content) = parse_uml_from_contents(input).expect("PlantUML couldn't be parsed!");
+        assert_eq!(content.relationships.len(), 1);
+        assert_eq!(
+            content.notes,
+            vec![
+                PlantUMLNote {
+                    alias: None,
+                    position: NotePosition::Left,
+                    target: NoteTarget::Element("Equipo"),
+                    text: vec!["máximo 11 jugadores"],
+                    links: vec![],
+                },
+                PlantUMLNote {
+                    alias: None,
+                    position: NotePosition::Right,
+                    target: NoteTarget::Member {
+                        element: "Equipo",
+                        member: "nombre"
+                    },
+                    text: vec!["único en el torneo"],
+                    links: vec![],
+                },
+                PlantUMLNote {
+                    alias: Some("N1"),
+                    position: NotePosition::None,
+                    target: NoteTarget::Floating,
+                    text: vec!["Regla de negocio"],
+                    links: vec!["Equipo"],
+                },
+                PlantUMLNote {
+                    alias: None,
+                    position: NotePosition::None,
+                    target: NoteTarget::Relationship(0),
+                    text: vec!["un jugador pertenece a un solo equipo"],
+                    links: vec![],
+                },
+            ]
+        );
+        assert_eq!(content.relationships[0].to, "Jugador");
+    }
+
+    #[test]
+    fn parse_content_with_associations_works() {
+        let input = "class Estudiante
+class Curso
+class Inscripcion
+class Profesor
+Estudiante \"*\" -- \"*\" Curso
+(Estudiante, Curso) .. Inscripcion
+<> imparte
+imparte -- Profesor
+imparte -- Curso
+Estudiante -- imparte
+";
+        let (_, content) = parse_uml_from_contents(input).expect("PlantUML couldn't be parsed!");
+        assert_eq!(
+            content.association_classes,
+            vec![PlantUMLAssociationClass {
+                ends: ("Estudiante", "Curso"),
+                class: "Inscripcion",
+                relationship: Some(0)
+            }]
+        );
+        assert_eq!(
+            content.diamonds,
+            vec![PlantUMLDiamond {
+                name: "imparte",
+                participants: vec!["Profesor", "Curso", "Estudiante"]
+            }]
+        );
+        assert_eq!(content.relationships.len(), 4);
+    }
+
+    #[test]
+    fn parse_content_with_lollipops_works() {
+        let input = "interface IComparable
+class Equipo
+Equipo ()- IComparable
+IRepositorio -() Equipo
+Equipo ()- modelo.ISerializable
+";
+        let (_, content) = parse_uml_from_contents(input).expect("PlantUML couldn't be parsed!");
+        let interfaces: Vec<(&[&str], &str)> = content
+            .interfaces
+            .iter()
+            .map(|i| (&i.namespace[..], i.name))
+            .collect();
+        assert_eq!(
+            interfaces,
+            vec![
+                (&[][..], "IComparable"),
+                (&[][..], "IRepositorio"),
+                (&["modelo"][..], "ISerializable"),
+            ]
+        );
+        assert_eq!(content.packages[0].name, "modelo");
+        assert!(content.find_element("modelo.ISerializable").is_some());
+    }
+
+    #[test]
+    fn parse_content_with_declarations_works() {
+        let input = "class Jugador
+interface Comparable
+enum Color
+class Vacio {}
+class Equipo {
+    - jugadores: Jugador[]
+}\n";
+        let (res, content) = parse_uml_from_contents(input).expect("PlantUML couldn't be parsed!");
+        assert_eq!("", res);
+        assert_eq!(
+            PlantUMLFile {
+                relationships: vec![],
+                association_classes: vec![],
+                diamonds: vec![],
+                notes: vec![],
+                packages: vec![],
+                groups: vec![],
+                directives: vec![],
+                metadata: PlantUMLMetadata::default(),
+                namespace_separator: Some("."),
+                comments: vec![],
+                classes: vec![
+                    PlantUMLClass {
+                        name: "Jugador",
+                        display_name: "Jugador",
+                        namespace: vec![],
+                        style: None,
+                        stereotypes: vec![],
+                        tags: vec![],
+                        link: None,
+                        fields: vec![],
+                        methods: vec![],
+                        span: Span::new(0, 13)
+                    },
+                    PlantUMLClass {
+                        name: "Vacio",
+                        display_name: "Vacio",
+                        namespace: vec![],
+                        style: None,
+                        stereotypes: vec![],
+                        tags: vec![],
+                        link: None,
+                        fields: vec![],
+                        methods: vec![],
+                        span: Span::new(46, 60)
+                    },
+                    PlantUMLClass {
+                        name: "Equipo",
+                        display_name: "Equipo",
+                        namespace: vec![],
+                        style: None,
+                        stereotypes: vec![],
+                        tags: vec![],
+                        link: None,
+                        fields: vec![PlantUMLField {
+                            name: "jugadores",
+                            accessibility: Accessibility::Private,
+                            field_type: "Jugador[]",
+                            modifier: Modifier::None,
+                            link: None,
+                            span: Span::new(80, 102)
+                        }],
+                        methods: vec![],
+                        span: Span::new(61, 104)
+                    }
+                ],
+                interfaces: vec![PlantUMLInterface {
+                    name: "Comparable",
+                    display_name: "Comparable",
+                    namespace: vec![],
+                    style: None,
+                    stereotypes: vec![],
+                    tags: vec![],
+                    link: None,
+                    methods: vec![],
+                    span: Span::new(14, 34)
+                }],
+                enums: vec![PlantUMLEnum {
+                    name: "Color",
+                    display_name: "Color",
+                    namespace: vec![],
+                    style: None,
+                    stereotypes: vec![],
+                    tags: vec![],
+                    link: None,
+                    variants: vec![],
+                    span: Span::new(35, 45)
+                }]
+            },
+            content
+        );
     }
 
     #[test]
@@ -167,62 +1613,316 @@ enum AccionUsuario {
         assert_eq!("", res);
         assert_eq!(
             PlantUMLFile {
+                relationships: vec![],
+                association_classes: vec![],
+                diamonds: vec![],
+                notes: vec![],
+                packages: vec![],
+                groups: vec![],
+                directives: vec![],
+                metadata: PlantUMLMetadata::default(),
+                namespace_separator: Some("."),
+                comments: vec![],
                 enums: vec![PlantUMLEnum {
                     name: "AccionUsuario",
+                    display_name: "AccionUsuario",
+                    namespace: vec![],
+                    style: None,
+                    stereotypes: vec![],
+                    tags: vec![],
+                    link: None,
                     variants: vec![
-                        PlantUMLEnumVariant { name: "SALIR" },
                         PlantUMLEnumVariant {
-                            name: "TOTAL_GOLES"
+                            name: "SALIR",
+                            span: Span::new(209, 214)
+                        },
+                        PlantUMLEnumVariant {
+                            name: "TOTAL_GOLES",
+                            span: Span::new(219, 230)
                         },
                         PlantUMLEnumVariant {
-                            name: "TOTAL_TIROS_ESQUINA"
+                            name: "TOTAL_TIROS_ESQUINA",
+                            span: Span::new(235, 254)
                         },
                         PlantUMLEnumVariant {
-                            name: "TOTAL_TARJETAS_AMARILLAS"
+                            name: "TOTAL_TARJETAS_AMARILLAS",
+                            span: Span::new(259, 283)
                         },
                         PlantUMLEnumVariant {
-                            name: "TOTAL_TARJETAS_ROJAS"
+                            name: "TOTAL_TARJETAS_ROJAS",
+                            span: Span::new(288, 308)
                         },
-                    ]
+                    ],
+                    span: Span::new(184, 310)
                 }],
                 interfaces: vec![],
                 classes: vec![PlantUMLClass {
                     name: "VentanaPrograma",
+                    display_name: "VentanaPrograma",
+                    namespace: vec![],
+                    style: None,
+                    stereotypes: vec![],
+                    tags: vec![],
+                    link: None,
                     fields: vec![PlantUMLField {
                         name: "analizador",
                         accessibility: Accessibility::Private,
                         field_type: "AnalizadorEquipos",
-                        modifier: Modifier::None
+                        modifier: Modifier::None,
+                        link: None,
+                        span: Span::new(28, 59)
                     }],
                     methods: vec![
                         PlantUMLMethod {
                             name: "VentanaPrograma",
                             accessibility: Accessibility::Public,
                             modifier: Modifier::Constructor,
+                            link: None,
                             return_type: "",
-                            arguments: vec![]
+                            arguments: vec![],
+                            span: Span::new(64, 90)
                         },
                         PlantUMLMethod {
                             name: "main",
                             accessibility: Accessibility::Public,
                             modifier: Modifier::Static,
+                            link: None,
                             return_type: "void",
                             arguments: vec![MethodArgument {
                                 name: "args",
-                                argument_type: "String[]"
-                            }]
+                                argument_type: "String[]",
+                                span: Span::new(116, 129)
+                            }],
+                            span: Span::new(95, 130)
                         },
                         PlantUMLMethod {
                             name: "preguntarUsuario",
                             accessibility: Accessibility::Public,
                             modifier: Modifier::Abstract,
+                            link: None,
                             return_type: "AccionUsuario",
-                            arguments: vec![]
+                            arguments: vec![],
+                            span: Span::new(135, 180)
                         }
-                    ]
+                    ],
+                    span: Span::new(0, 182)
                 }]
             },
             content
         );
     }
+
+    #[test]
+    fn parse_content_with_directives_works() {
+        let input = "class Equipo <<Entity>> {
+- nombre: String
++ String getNombre()
+}
+class Jugador <<Entity>>
+class Cache $internal
+class Suelta
+note left of Cache : solo en memoria
+Equipo *-- Jugador
+Equipo --> Cache
+hide empty members
+hide methods
+show <<Entity>> methods
+hide Jugador
+remove @unlinked
+remove $internal
+";
+        let (_, content) = parse_uml_from_contents(input).expect("PlantUML couldn't be parsed!");
+        assert_eq!(content.directives.len(), 6);
+        assert_eq!(content.classes[0].stereotypes, vec!["Entity"]);
+        assert_eq!(content.classes[2].tags, vec!["internal"]);
+
+        let visible = content.visible_model();
+        assert_eq!(
+            visible
+                .classes
+                .iter()
+                .map(|class| class.name)
+                .collect::<Vec<_>>(),
+            vec!["Equipo"]
+        );
+        assert_eq!(visible.classes[0].fields.len(), 1);
+        assert_eq!(visible.classes[0].methods.len(), 1);
+        assert!(visible.relationships.is_empty());
+        assert!(visible.notes.is_empty());
+        assert_eq!(content.classes.len(), 4);
+    }
+
+    #[test]
+    fn parse_content_with_metadata_works() {
+        let input = "title Modelo de Equipos
+left to right direction
+skinparam classAttributeIconSize 0
+package modelo {
+skinparam class {
+  BackgroundColor PaleGreen
+}
+class Equipo
+legend right
+  Formato: { nombre }
+endlegend
+class Jugador
+}
+";
+        let (rest, content) = parse_uml_from_contents(input).expect("PlantUML couldn't be parsed!");
+        assert_eq!("", rest);
+        assert_eq!(
+            content.metadata.title.map(|title| title.lines),
+            Some(vec!["Modelo de Equipos"])
+        );
+        assert_eq!(
+            content.metadata.legend.map(|legend| legend.lines),
+            Some(vec!["Formato: { nombre }"])
+        );
+        assert_eq!(
+            content.metadata.direction,
+            Some(metadata::LayoutDirection::LeftToRight)
+        );
+        assert_eq!(content.metadata.skinparams.len(), 2);
+        assert_eq!(content.classes[1].namespace, vec!["modelo"]);
+    }
+
+    #[test]
+    fn parse_content_with_groups_works() {
+        let input = "package modelo {
+together {
+class Equipo
+together {
+class Jugador
+}
+}
+class Torneo
+}
+class Partido
+";
+        let (rest, content) = parse_uml_from_contents(input).expect("PlantUML couldn't be parsed!");
+        assert_eq!("", rest);
+        assert_eq!(
+            content.groups,
+            vec![
+                PlantUMLGroup {
+                    parent: None,
+                    elements: vec!["Equipo"],
+                },
+                PlantUMLGroup {
+                    parent: Some(0),
+                    elements: vec!["Jugador"],
+                },
+            ]
+        );
+        assert_eq!(content.classes[1].namespace, vec!["modelo"]);
+        assert_eq!(content.classes[2].namespace, vec!["modelo"]);
+        assert!(content.classes[3].namespace.is_empty());
+
+        let input = "class togetherness\nclass Equipo\ntogetherness --> Equipo\n";
+        let (_, content) = parse_uml_from_contents(input).expect("PlantUML couldn't be parsed!");
+        assert!(content.groups.is_empty());
+        assert_eq!(content.relationships[0].from, "togetherness");
+    }
+
+    #[test]
+    fn parse_content_with_unclosed_blocks_fails() {
+        let errors =
+            parse_uml_strict("package p {\n class A\n").expect_err("PlantUML shouldn't be parsed!");
+        let errors: Vec<_> = errors
+            .iter()
+            .map(|error| (error.kind, error.element, error.text, error.help))
+            .collect();
+        assert_eq!(
+            errors,
+            vec![(
+                ParseErrorKind::MissingBrace,
+                ElementKind::Package,
+                "package p {",
+                Some(Help::CloseBody)
+            )]
+        );
+
+        let input = "together {\nclass A\npackage Modelo Equipos {\nclass B\npackage q {\n}\n";
+        let (content, errors, warnings) = parse_uml_recovering(input);
+        let errors: Vec<_> = errors
+            .iter()
+            .map(|error| (error.kind, error.element, error.line))
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                (ParseErrorKind::MissingBrace, ElementKind::Group, 1),
+                (ParseErrorKind::MissingBrace, ElementKind::Package, 3),
+                (ParseErrorKind::UnexpectedToken, ElementKind::Package, 3),
+            ]
+        );
+        assert!(warnings.is_empty());
+        assert_eq!(content.groups[0].elements, vec!["A", "B"]);
+        assert_eq!(content.packages[0].name, "q");
+    }
+
+    #[test]
+    fn parse_content_with_hyperlinks_works() {
+        let input = "class Equipo [[https://wiki/Equipo{Equipos del torneo}]] {
+- nombre: String [[https://wiki/Equipo#nombre]]
+}
+url of Jugador is [[https://wiki/Jugador]]
+enum Posicion
+class Jugador
+";
+        let (_, content) = parse_uml_from_contents(input).expect("PlantUML couldn't be parsed!");
+        assert_eq!(content.classes[0].name, "Equipo");
+        assert_eq!(
+            content.classes[0].link.and_then(|link| link.tooltip),
+            Some("Equipos del torneo")
+        );
+        assert_eq!(content.classes[0].fields[0].field_type, "String");
+        assert_eq!(
+            content.classes[0].fields[0].link.and_then(|link| link.url),
+            Some("https://wiki/Equipo#nombre")
+        );
+        assert_eq!(
+            content
+                .find_element("Jugador")
+                .and_then(|element| element.link())
+                .and_then(|link| link.url),
+            Some("https://wiki/Jugador")
+        );
+        assert_eq!(content.enums[0].link, None);
+    }
+
+    #[test]
+    fn parse_content_spans_works() {
+        let input = "\u{feff}title Modelo
+package modelo {
+  class Equipo {
+    - nombre: String
+    + void fichar(Jugador jugador)
+  }
+}
+enum Color {
+  ROJO
+}
+";
+        let (_, content) = parse_uml_from_contents(input).expect("PlantUML couldn't be parsed!");
+        let equipo = &content.classes[0];
+        assert!(input[equipo.span.start..equipo.span.end].starts_with("class Equipo {"));
+        assert!(input[equipo.span.start..equipo.span.end].ends_with('}'));
+        assert_eq!(equipo.span.line_column(input), (3, 3));
+
+        let field = &equipo.fields[0];
+        assert_eq!(&input[field.span.start..field.span.end], "- nombre: String");
+        assert_eq!(field.span.line_column(input), (4, 5));
+
+        let argument = &equipo.methods[0].arguments[0];
+        assert_eq!(
+            &input[argument.span.start..argument.span.end],
+            "Jugador jugador"
+        );
+        assert_eq!(argument.span.line_column(input), (5, 19));
+
+        let variant = &content.enums[0].variants[0];
+        assert_eq!(&input[variant.span.start..variant.span.end], "ROJO");
+        assert_eq!(variant.span.line_column(input), (9, 3));
+    }
 }
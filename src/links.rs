@@ -0,0 +1,148 @@
+use crate::elements::line_terminator;
+use crate::relationships::parse_reference;
+use nom::bytes::complete::tag;
+use nom::bytes::complete::take_until;
+use nom::character::complete::space0;
+use nom::character::complete::space1;
+use nom::sequence::delimited;
+use nom::sequence::preceded;
+use nom::sequence::terminated;
+use nom::sequence::tuple;
+use nom::IResult;
+
+/// A hyperlink like `[[https://wiki/Foo{Página del wiki} Foo]]`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Hyperlink<'a> {
+    /// It's `None` for links that only have a tooltip, like `[[{Solo un tooltip}]]`.
+    pub url: Option<&'a str>,
+    /// Text between braces after the url.
+    pub tooltip: Option<&'a str>,
+    /// Text shown instead of the url.
+    pub label: Option<&'a str>,
+}
+
+fn non_empty(text: &str) -> Option<&str> {
+    Some(text.trim()).filter(|text| !text.is_empty())
+}
+
+/// Parses a hyperlink between double brackets.
+pub fn parse_hyperlink(element: &str) -> IResult<&str, Hyperlink<'_>> {
+    let (rest, content) = delimited(tag("[["), take_until("]]"), tag("]]"))(element)?;
+    let content = content.trim();
+
+    let (url, tooltip, label) = match content.split_once('{') {
+        Some((url, after)) => match after.split_once('}') {
+            Some((tooltip, label)) => (url, Some(tooltip.trim()), label),
+            None => (content, None, ""),
+        },
+        None => match content.split_once(char::is_whitespace) {
+            Some((url, label)) => (url, None, label),
+            None => (content, None, ""),
+        },
+    };
+
+    Ok((
+        rest,
+        Hyperlink {
+            url: non_empty(url),
+            tooltip,
+            label: non_empty(label),
+        },
+    ))
+}
+
+/// Splits a hyperlink written at the end of a text, like the type of a field, from the text before it.
+pub fn split_trailing_hyperlink(text: &str) -> (&str, Option<Hyperlink<'_>>) {
+    let text = text.trim_end();
+    if text.ends_with("]]") {
+        if let Some(i) = text.rfind("[[") {
+            if let Ok(("", link)) = parse_hyperlink(&text[i..]) {
+                return (text[..i].trim_end(), Some(link));
+            }
+        }
+    }
+    (text, None)
+}
+
+/// Parses `url of Foo is [[...]]`, returns the reference of the element and its hyperlink.
+pub fn parse_url_of(element: &str) -> IResult<&str, (&str, Hyperlink<'_>)> {
+    tuple((
+        preceded(
+            tuple((tag("url"), space1, tag("of"), space1)),
+            parse_reference,
+        ),
+        delimited(
+            tuple((space1, tag("is"), space1)),
+            parse_hyperlink,
+            terminated(space0, line_terminator),
+        ),
+    ))(element.trim_start())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_hyperlink_works() {
+        assert_eq!(
+            parse_hyperlink("[[https://wiki/Foo]] {\n"),
+            Ok((
+                " {\n",
+                Hyperlink {
+                    url: Some("https://wiki/Foo"),
+                    tooltip: None,
+                    label: None,
+                }
+            ))
+        );
+        assert_eq!(
+            parse_hyperlink("[[https://wiki/Foo{Página del wiki} Ver Foo]]"),
+            Ok((
+                "",
+                Hyperlink {
+                    url: Some("https://wiki/Foo"),
+                    tooltip: Some("Página del wiki"),
+                    label: Some("Ver Foo"),
+                }
+            ))
+        );
+        assert_eq!(
+            parse_hyperlink("[[{Solo un tooltip}]]"),
+            Ok((
+                "",
+                Hyperlink {
+                    url: None,
+                    tooltip: Some("Solo un tooltip"),
+                    label: None,
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_hyperlink_fails() {
+        assert!(parse_hyperlink("[https://wiki/Foo]").is_err());
+        assert!(parse_hyperlink("[[https://wiki/Foo").is_err());
+    }
+
+    #[test]
+    fn parse_url_of_works() {
+        let (rest, (reference, link)) =
+            parse_url_of("url of Equipo is [[https://wiki/Equipo]]\nclass Equipo").unwrap();
+        assert_eq!("class Equipo", rest);
+        assert_eq!(reference, "Equipo");
+        assert_eq!(link.url, Some("https://wiki/Equipo"));
+
+        assert!(parse_url_of("url of Equipo [[https://wiki/Equipo]]\n").is_err());
+    }
+
+    #[test]
+    fn split_trailing_hyperlink_works() {
+        let (text, link) = split_trailing_hyperlink("String [[https://wiki/Nombre]]");
+        assert_eq!(text, "String");
+        assert_eq!(link.and_then(|link| link.url), Some("https://wiki/Nombre"));
+
+        assert_eq!(split_trailing_hyperlink("String[] "), ("String[]", None));
+    }
+}
@@ -0,0 +1,214 @@
+use crate::errors::ElementKind;
+use crate::errors::Help;
+use crate::errors::ParseError;
+use crate::errors::ParseErrorKind;
+use crate::modifiers::MODIFIERS;
+
+/// Language of the diagnostic messages. The codes of the errors are the same in every language.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum Language {
+    #[default]
+    English,
+    Spanish,
+}
+
+/// Words used by the renderer of diagnostics around the messages.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub(crate) enum Label {
+    Error,
+    Warning,
+    Help,
+}
+
+impl Language {
+    /// The language of a tag like `es`, `en` or `es-AR`.
+    pub fn from_tag(tag: &str) -> Option<Language> {
+        let primary = tag.split(['-', '_']).next().unwrap_or_default();
+        match primary.to_ascii_lowercase().as_str() {
+            "en" => Some(Language::English),
+            "es" => Some(Language::Spanish),
+            _ => None,
+        }
+    }
+
+    /// Short description of what went wrong, like `unexpected token`.
+    pub fn kind(&self, kind: ParseErrorKind) -> &'static str {
+        match self {
+            Language::English => match kind {
+                ParseErrorKind::UnexpectedToken => "unexpected token",
+                ParseErrorKind::MissingBrace => "missing closing brace",
+                ParseErrorKind::UnexpectedEnd => "unexpected end of input",
+                ParseErrorKind::InvalidIdentifier => "invalid identifier",
+                ParseErrorKind::UnknownModifier => "unknown modifier",
+                ParseErrorKind::UnrecognizedLine => "unrecognized line",
+                ParseErrorKind::UnmatchedBrace => "closing brace without an open block",
+                ParseErrorKind::UnsupportedPreprocessor => "unsupported preprocessor directive",
+                ParseErrorKind::UnresolvedReference => "reference to an undeclared element",
+                ParseErrorKind::UnresolvedInclude => "included file not found",
+                ParseErrorKind::IncludeTooDeep => "too many nested includes",
+            },
+            Language::Spanish => match kind {
+                ParseErrorKind::UnexpectedToken => "símbolo inesperado",
+                ParseErrorKind::MissingBrace => "falta la llave de cierre",
+                ParseErrorKind::UnexpectedEnd => "fin inesperado de la entrada",
+                ParseErrorKind::InvalidIdentifier => "identificador no válido",
+                ParseErrorKind::UnknownModifier => "modificador desconocido",
+                ParseErrorKind::UnrecognizedLine => "línea no reconocida",
+                ParseErrorKind::UnmatchedBrace => "llave de cierre sin un bloque abierto",
+                ParseErrorKind::UnsupportedPreprocessor => {
+                    "directiva del preprocesador no soportada"
+                }
+                ParseErrorKind::UnresolvedReference => "referencia a un elemento no declarado",
+                ParseErrorKind::UnresolvedInclude => "no se encontró el archivo incluido",
+                ParseErrorKind::IncludeTooDeep => "demasiados includes anidados",
+            },
+        }
+    }
+
+    /// The element with its article, like `the class`.
+    fn element(&self, element: ElementKind) -> &'static str {
+        match self {
+            Language::English => match element {
+                ElementKind::Class => "the class",
+                ElementKind::Interface => "the interface",
+                ElementKind::Enum => "the enum",
+                ElementKind::Package => "the package",
+                ElementKind::Note => "the note",
+                ElementKind::Diamond => "the diamond",
+                ElementKind::Group => "the together group",
+                ElementKind::Relationship => "the relationship",
+                ElementKind::Diagram => "the diagram",
+            },
+            Language::Spanish => match element {
+                ElementKind::Class => "la clase",
+                ElementKind::Interface => "la interfaz",
+                ElementKind::Enum => "el enum",
+                ElementKind::Package => "el paquete",
+                ElementKind::Note => "la nota",
+                ElementKind::Diamond => "el diamante",
+                ElementKind::Group => "el grupo together",
+                ElementKind::Relationship => "la relación",
+                ElementKind::Diagram => "el diagrama",
+            },
+        }
+    }
+
+    /// The suggestion on how to fix an error.
+    pub fn help(&self, help: Help) -> String {
+        let text = match (self, help) {
+            (Language::English, Help::DidYouMean(suggestion)) => {
+                return format!("did you mean `{}`?", suggestion)
+            }
+            (Language::Spanish, Help::DidYouMean(suggestion)) => {
+                return format!("¿quisiste decir `{}`?", suggestion)
+            }
+            (Language::English, Help::FieldNameWithSpaces) => "field names cannot contain spaces",
+            (Language::Spanish, Help::FieldNameWithSpaces) => {
+                "los nombres de los atributos no pueden contener espacios"
+            }
+            (Language::English, Help::ArgumentNameWithSpaces) => {
+                "argument names cannot contain spaces"
+            }
+            (Language::Spanish, Help::ArgumentNameWithSpaces) => {
+                "los nombres de los argumentos no pueden contener espacios"
+            }
+            (Language::English, Help::VariantNameWithSpaces) => {
+                "enum variant names cannot contain spaces"
+            }
+            (Language::Spanish, Help::VariantNameWithSpaces) => {
+                "los valores de un enum no pueden contener espacios"
+            }
+            (Language::English, Help::CloseBody) => "add a `}` to close the body",
+            (Language::Spanish, Help::CloseBody) => "añade una `}` para cerrar el cuerpo",
+            (Language::English, Help::CloseNote) => "add an `end note` line to close the note",
+            (Language::Spanish, Help::CloseNote) => {
+                "añade una línea `end note` para cerrar la nota"
+            }
+            (Language::English, Help::KnownModifiers) => {
+                return format!("the known modifiers are {}", list_modifiers("and"))
+            }
+            (Language::Spanish, Help::KnownModifiers) => {
+                return format!("los modificadores conocidos son {}", list_modifiers("y"))
+            }
+        };
+        text.to_string()
+    }
+
+    /// The error without its location, like `unexpected token while parsing the class`.
+    pub fn message(&self, error: &ParseError) -> String {
+        let kind = self.kind(error.kind);
+        let element = self.element(error.element);
+        match self {
+            Language::English => format!("{} while parsing {}", kind, element),
+            Language::Spanish => format!("{} al analizar {}", kind, element),
+        }
+    }
+
+    pub(crate) fn label(&self, label: Label) -> &'static str {
+        match (self, label) {
+            (Language::English, Label::Error) => "error",
+            (Language::English, Label::Warning) => "warning",
+            (Language::English, Label::Help) => "help",
+            (Language::Spanish, Label::Error) => "error",
+            (Language::Spanish, Label::Warning) => "aviso",
+            (Language::Spanish, Label::Help) => "ayuda",
+        }
+    }
+}
+
+/// The known modifiers between backticks, like `` `{static}`, `{abstract}` and `{ctor}` ``.
+fn list_modifiers(and: &str) -> String {
+    let modifiers: Vec<String> = MODIFIERS
+        .iter()
+        .map(|modifier| format!("`{}`", modifier))
+        .collect();
+    match modifiers.split_last() {
+        Some((last, [])) => last.clone(),
+        Some((last, rest)) => format!("{} {} {}", rest.join(", "), and, last),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spans::Span;
+
+    #[test]
+    fn from_tag_works() {
+        assert_eq!(Language::from_tag("es"), Some(Language::Spanish));
+        assert_eq!(Language::from_tag("es-AR"), Some(Language::Spanish));
+        assert_eq!(Language::from_tag("EN_us"), Some(Language::English));
+        assert_eq!(Language::from_tag("fr"), None);
+    }
+
+    #[test]
+    fn message_works() {
+        let error = ParseError {
+            kind: ParseErrorKind::InvalidIdentifier,
+            element: ElementKind::Class,
+            text: "nombre Equipo",
+            span: Span::new(19, 32),
+            line: 2,
+            column: 5,
+            help: Some(Help::FieldNameWithSpaces),
+        };
+        assert_eq!(
+            Language::English.message(&error),
+            "invalid identifier while parsing the class"
+        );
+        assert_eq!(
+            Language::Spanish.message(&error),
+            "identificador no válido al analizar la clase"
+        );
+        assert_eq!(
+            Language::Spanish.help(Help::DidYouMean("class")),
+            "¿quisiste decir `class`?"
+        );
+        assert_eq!(
+            Language::Spanish.help(Help::KnownModifiers),
+            "los modificadores conocidos son `{static}`, `{abstract}`, `{virtual}`, `{ctor}`, \
+             `{classifier}`, `{field}` y `{method}`"
+        );
+    }
+}
@@ -0,0 +1,311 @@
+use crate::elements::line_terminator;
+use crate::elements::parse_block_lines;
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::bytes::complete::take_till1;
+use nom::character::complete::char;
+use nom::character::complete::multispace0;
+use nom::character::complete::not_line_ending;
+use nom::character::complete::space0;
+use nom::character::complete::space1;
+use nom::combinator::map;
+use nom::combinator::value;
+use nom::error::Error;
+use nom::multi::many0;
+use nom::sequence::delimited;
+use nom::sequence::preceded;
+use nom::sequence::terminated;
+use nom::sequence::tuple;
+use nom::IResult;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TextPosition {
+    Left,
+    Right,
+    Center,
+    Top,
+    Bottom,
+}
+
+/// Text of a title, header, footer, caption or legend.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PlantUMLText<'a> {
+    /// Positions written around the keyword, like `right` in `right header` or `top left` in
+    /// `legend top left`.
+    pub position: Vec<TextPosition>,
+    /// Lines of the text, without indentation.
+    pub lines: Vec<&'a str>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum LayoutDirection {
+    /// `top to bottom direction`, the default one.
+    TopToBottom,
+    /// `left to right direction`
+    LeftToRight,
+}
+
+/// A `skinparam` like `skinparam classAttributeIconSize 0`. Blocks like `skinparam class { ... }`
+/// have no value and keep their parameters as children.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SkinParam<'a> {
+    pub name: &'a str,
+    pub value: Option<&'a str>,
+    pub children: Vec<SkinParam<'a>>,
+}
+
+/// Everything in a diagram that isn't part of the model.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct PlantUMLMetadata<'a> {
+    pub title: Option<PlantUMLText<'a>>,
+    pub header: Option<PlantUMLText<'a>>,
+    pub footer: Option<PlantUMLText<'a>>,
+    pub caption: Option<PlantUMLText<'a>>,
+    pub legend: Option<PlantUMLText<'a>>,
+    pub direction: Option<LayoutDirection>,
+    pub skinparams: Vec<SkinParam<'a>>,
+}
+
+/// A single metadata line (or block) of a diagram.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum MetadataEntry<'a> {
+    Title(PlantUMLText<'a>),
+    Header(PlantUMLText<'a>),
+    Footer(PlantUMLText<'a>),
+    Caption(PlantUMLText<'a>),
+    Legend(PlantUMLText<'a>),
+    Direction(LayoutDirection),
+    SkinParam(SkinParam<'a>),
+}
+
+impl<'a> PlantUMLMetadata<'a> {
+    /// Adds an entry to the metadata, texts declared more than once keep the last value.
+    pub fn insert(&mut self, entry: MetadataEntry<'a>) {
+        match entry {
+            MetadataEntry::Title(text) => self.title = Some(text),
+            MetadataEntry::Header(text) => self.header = Some(text),
+            MetadataEntry::Footer(text) => self.footer = Some(text),
+            MetadataEntry::Caption(text) => self.caption = Some(text),
+            MetadataEntry::Legend(text) => self.legend = Some(text),
+            MetadataEntry::Direction(direction) => self.direction = Some(direction),
+            MetadataEntry::SkinParam(skinparam) => self.skinparams.push(skinparam),
+        }
+    }
+}
+
+fn parse_position(element: &str) -> IResult<&str, TextPosition> {
+    alt((
+        value(TextPosition::Left, tag("left")),
+        value(TextPosition::Right, tag("right")),
+        value(TextPosition::Center, tag("center")),
+        value(TextPosition::Top, tag("top")),
+        value(TextPosition::Bottom, tag("bottom")),
+    ))(element)
+}
+
+/// Parses a text that is either written on the same line as its keyword (`title Modelo`) or on the
+/// following lines until `end <keyword>` is found.
+fn parse_text(keyword: &'static str) -> impl Fn(&str) -> IResult<&str, PlantUMLText<'_>> {
+    move |element| {
+        let (rest, mut position) = many0(terminated(parse_position, space1))(element)?;
+        let (rest, _) = tag(keyword)(rest)?;
+        let (rest, after) = many0(preceded(space1, parse_position))(rest)?;
+        position.extend(after);
+
+        if let Ok((rest, _)) = preceded(space0, line_terminator)(rest) {
+            let (rest, lines) = parse_block_lines(rest, keyword)?;
+            return Ok((rest, PlantUMLText { position, lines }));
+        }
+        let (rest, line) = delimited(space1, not_line_ending, line_terminator)(rest)?;
+        Ok((
+            rest,
+            PlantUMLText {
+                position,
+                lines: vec![line.trim()],
+            },
+        ))
+    }
+}
+
+fn parse_direction(element: &str) -> IResult<&str, LayoutDirection> {
+    terminated(
+        alt((
+            value(LayoutDirection::LeftToRight, tag("left to right")),
+            value(LayoutDirection::TopToBottom, tag("top to bottom")),
+        )),
+        tuple((space1, tag("direction"), space0, line_terminator)),
+    )(element)
+}
+
+/// Parses a parameter of a `skinparam` without the keyword, it's also used for the parameters inside
+/// a block.
+fn parse_skinparam_entry(element: &str) -> IResult<&str, SkinParam<'_>> {
+    let (rest, name) = take_till1(|c: char| c.is_whitespace() || c == '{')(element)?;
+
+    if let Ok((mut rest, _)) =
+        tuple((space0, char::<_, Error<&str>>('{'), space0, line_terminator))(rest)
+    {
+        let mut children = vec![];
+        loop {
+            if let Ok((rest, _)) = preceded(multispace0, char::<_, Error<&str>>('}'))(rest) {
+                let skinparam = SkinParam {
+                    name,
+                    value: None,
+                    children,
+                };
+                return Ok((rest, skinparam));
+            }
+            let (next, child) = preceded(multispace0, parse_skinparam_entry)(rest)?;
+            children.push(child);
+            rest = next;
+        }
+    }
+
+    let (rest, value) = delimited(space1, not_line_ending, line_terminator)(rest)?;
+    Ok((
+        rest,
+        SkinParam {
+            name,
+            value: Some(value.trim()),
+            children: vec![],
+        },
+    ))
+}
+
+/// Parses a `skinparam`, either a single parameter or a block of them.
+pub fn parse_skinparam(element: &str) -> IResult<&str, SkinParam<'_>> {
+    preceded(tuple((tag("skinparam"), space1)), parse_skinparam_entry)(element.trim_start())
+}
+
+/// Parses a title, header, footer, caption, legend, layout direction or skinparam.
+pub fn parse_metadata_entry(element: &str) -> IResult<&str, MetadataEntry<'_>> {
+    alt((
+        map(parse_text("title"), MetadataEntry::Title),
+        map(parse_text("header"), MetadataEntry::Header),
+        map(parse_text("footer"), MetadataEntry::Footer),
+        map(parse_text("caption"), MetadataEntry::Caption),
+        map(parse_text("legend"), MetadataEntry::Legend),
+        map(parse_direction, MetadataEntry::Direction),
+        map(parse_skinparam, MetadataEntry::SkinParam),
+    ))(element.trim_start())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_single_line_texts_works() {
+        assert_eq!(
+            parse_metadata_entry("title Modelo de Equipos\nclass Equipo"),
+            Ok((
+                "class Equipo",
+                MetadataEntry::Title(PlantUMLText {
+                    position: vec![],
+                    lines: vec!["Modelo de Equipos"],
+                })
+            ))
+        );
+        assert_eq!(
+            parse_metadata_entry("right footer Página 1"),
+            Ok((
+                "",
+                MetadataEntry::Footer(PlantUMLText {
+                    position: vec![TextPosition::Right],
+                    lines: vec!["Página 1"],
+                })
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_multi_line_texts_works() {
+        let input = "legend top left
+  Equipo: conjunto de jugadores
+  Jugador: miembro de un equipo
+endlegend
+";
+        assert_eq!(
+            parse_metadata_entry(input),
+            Ok((
+                "",
+                MetadataEntry::Legend(PlantUMLText {
+                    position: vec![TextPosition::Top, TextPosition::Left],
+                    lines: vec![
+                        "Equipo: conjunto de jugadores",
+                        "Jugador: miembro de un equipo"
+                    ],
+                })
+            ))
+        );
+
+        let (_, entry) = parse_metadata_entry("title\n Modelo\n de Equipos\nend title")
+            .expect("Couldn't parse the title!");
+        assert_eq!(
+            entry,
+            MetadataEntry::Title(PlantUMLText {
+                position: vec![],
+                lines: vec!["Modelo", "de Equipos"],
+            })
+        );
+    }
+
+    #[test]
+    fn parse_direction_works() {
+        assert_eq!(
+            parse_metadata_entry("left to right direction\n"),
+            Ok(("", MetadataEntry::Direction(LayoutDirection::LeftToRight)))
+        );
+        assert!(parse_metadata_entry("left to right\n").is_err());
+    }
+
+    #[test]
+    fn parse_skinparam_works() {
+        assert_eq!(
+            parse_skinparam("skinparam classAttributeIconSize 0\n"),
+            Ok((
+                "",
+                SkinParam {
+                    name: "classAttributeIconSize",
+                    value: Some("0"),
+                    children: vec![],
+                }
+            ))
+        );
+
+        let input = "skinparam class {
+  BackgroundColor PaleGreen
+  BorderColor<<Entity>> #FF0000
+}
+class Equipo";
+        assert_eq!(
+            parse_skinparam(input),
+            Ok((
+                "\nclass Equipo",
+                SkinParam {
+                    name: "class",
+                    value: None,
+                    children: vec![
+                        SkinParam {
+                            name: "BackgroundColor",
+                            value: Some("PaleGreen"),
+                            children: vec![],
+                        },
+                        SkinParam {
+                            name: "BorderColor<<Entity>>",
+                            value: Some("#FF0000"),
+                            children: vec![],
+                        },
+                    ],
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_metadata_entry_fails() {
+        assert!(parse_metadata_entry("legend\n  sin final\n").is_err());
+        assert!(parse_metadata_entry("skinparam class {\n  BackgroundColor red\n").is_err());
+        assert!(parse_metadata_entry("titles Modelo\n").is_err());
+    }
+}
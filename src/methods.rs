@@ -1,28 +1,49 @@
 use crate::accessibilities::parse_accessibility;
 use crate::accessibilities::Accessibility;
 use crate::field::doesnt_have_spaces;
+use crate::links::parse_hyperlink;
+use crate::links::Hyperlink;
 use crate::modifiers::parse_modifier;
 use crate::modifiers::Modifier;
+use crate::spans::Span;
+use crate::spans::Spanned;
 use crate::IResult;
 use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::bytes::complete::take_till1;
 use nom::bytes::complete::take_until1;
 use nom::character::complete::space0;
 use nom::character::streaming::char;
+use nom::combinator::consumed;
 use nom::combinator::opt;
 use nom::multi::many0;
+use nom::sequence::pair;
 use nom::sequence::preceded;
+use nom::sequence::tuple;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct MethodArgument<'a> {
     pub name: &'a str,
     pub argument_type: &'a str,
+    pub span: Span,
 }
 
-pub fn parse_method_argument(element: &str) -> IResult<&str, MethodArgument> {
+impl Spanned for MethodArgument<'_> {
+    fn span(&self) -> Span {
+        self.span
+    }
+
+    fn shift(&mut self, offset: usize) {
+        self.span = self.span.shifted(offset);
+    }
+}
+
+pub fn parse_method_argument(element: &str) -> IResult<&str, MethodArgument<'_>> {
     let type_parser = take_until1(" ");
     let mut name_parser = alt((take_until1(","), take_until1(")")));
 
-    let (rest, argument_type) = type_parser(element.trim_start())?;
+    let trimmed = element.trim_start();
+    let (rest, argument_type) = type_parser(trimmed)?;
     let parsed_name = name_parser(rest.trim_start())?;
     let (rest, name) = doesnt_have_spaces(parsed_name)?;
 
@@ -31,29 +52,87 @@ pub fn parse_method_argument(element: &str) -> IResult<&str, MethodArgument> {
         MethodArgument {
             name,
             argument_type,
+            span: Span::between(element, trimmed, rest),
         },
     ))
 }
 
-pub fn parse_method_arguments(element: &str) -> IResult<&str, Vec<MethodArgument>> {
-    many0(preceded(
-        preceded(opt(char(',')), space0),
-        parse_method_argument,
-    ))(element)
+/// Parses an argument written with the type after the name, like `nombre: String`.
+pub fn parse_uml_method_argument(element: &str) -> IResult<&str, MethodArgument<'_>> {
+    let trimmed = element.trim_start();
+    let (rest, name) = take_till1(|c: char| matches!(c, ':' | ',' | ')'))(trimmed)?;
+    let (rest, _) = tag(":")(rest)?;
+    let (rest, argument_type) = take_till1(|c: char| c == ',' || c == ')')(rest)?;
+    let (rest, name) = doesnt_have_spaces((rest, name.trim()))?;
+    let argument_type = argument_type.trim();
+    if argument_type.is_empty() {
+        return Err(nom::Err::Error(nom::error::Error::new(
+            rest,
+            nom::error::ErrorKind::Verify,
+        )));
+    }
+
+    Ok((
+        rest,
+        MethodArgument {
+            name,
+            argument_type,
+            span: Span::between(element, trimmed, rest),
+        },
+    ))
+}
+
+pub fn parse_method_arguments(element: &str) -> IResult<&str, Vec<MethodArgument<'_>>> {
+    parse_arguments(element, parse_method_argument)
+}
+
+pub fn parse_uml_method_arguments(element: &str) -> IResult<&str, Vec<MethodArgument<'_>>> {
+    parse_arguments(element, parse_uml_method_argument)
+}
+
+fn parse_arguments<'a>(
+    element: &'a str, parser: fn(&'a str) -> IResult<&'a str, MethodArgument<'a>>,
+) -> IResult<&'a str, Vec<MethodArgument<'a>>> {
+    let (rest, arguments) =
+        many0(preceded(preceded(opt(char(',')), space0), consumed(parser)))(element)?;
+    let arguments = arguments
+        .into_iter()
+        .map(|(consumed, mut argument)| {
+            argument.shift(Span::of(element, consumed).start);
+            argument
+        })
+        .collect();
+    Ok((rest, arguments))
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct PlantUMLMethod<'a> {
     pub name: &'a str,
     pub return_type: &'a str,
     pub accessibility: Accessibility,
     pub arguments: Vec<MethodArgument<'a>>,
     pub modifier: Modifier,
+    pub link: Option<Hyperlink<'a>>,
+    pub span: Span,
+}
+
+impl Spanned for PlantUMLMethod<'_> {
+    fn span(&self) -> Span {
+        self.span
+    }
+
+    fn shift(&mut self, offset: usize) {
+        self.span = self.span.shifted(offset);
+        for argument in self.arguments.iter_mut() {
+            argument.shift(offset);
+        }
+    }
 }
 
 // + void setNombreEquipo(String nombre)
-pub fn parse_method(element: &str) -> IResult<&str, PlantUMLMethod> {
-    let (rest, accessibility) = parse_accessibility(element.trim_start())?;
+pub fn parse_method(element: &str) -> IResult<&str, PlantUMLMethod<'_>> {
+    let trimmed = element.trim_start();
+    let (rest, accessibility) = parse_accessibility(trimmed)?;
     let (rest, modifier) = parse_modifier(rest.trim_start())?;
     let (rest, return_type) = if modifier == Modifier::Constructor {
         (rest, "")
@@ -61,16 +140,57 @@ pub fn parse_method(element: &str) -> IResult<&str, PlantUMLMethod> {
         take_until1(" ")(rest.trim_start())?
     };
     let (rest, name) = take_until1("(")(rest.trim_start())?;
-    let (rest, arguments) = parse_method_arguments(rest[1..].trim_start())?;
+    let (arguments_input, _) = pair(char('('), space0)(rest)?;
+    let (rest, mut arguments) = parse_method_arguments(arguments_input)?;
+    for argument in arguments.iter_mut() {
+        argument.shift(Span::of(element, arguments_input).start);
+    }
+    let (rest, _) = preceded(space0, tag(")"))(rest)?;
+    let (rest, link) = opt(preceded(space0, parse_hyperlink))(rest)?;
 
     Ok((
-        &rest[1..],
+        rest,
         PlantUMLMethod {
             accessibility,
             modifier,
             return_type,
             name,
             arguments,
+            link,
+            span: Span::between(element, trimmed, rest),
+        },
+    ))
+}
+
+/// Parses a method written with the types after the names, like `+ setNombre(nombre: String): void`.
+/// The return type is empty when the method doesn't have one, like a constructor.
+pub fn parse_uml_method(element: &str) -> IResult<&str, PlantUMLMethod<'_>> {
+    let trimmed = element.trim_start();
+    let (rest, accessibility) = parse_accessibility(trimmed)?;
+    let (rest, modifier) = parse_modifier(rest.trim_start())?;
+    let (rest, name) = take_till1(|c: char| c == '(' || c.is_whitespace())(rest.trim_start())?;
+    let (arguments_input, _) = tag("(")(rest)?;
+    let (rest, mut arguments) = parse_uml_method_arguments(arguments_input)?;
+    for argument in arguments.iter_mut() {
+        argument.shift(Span::of(element, arguments_input).start);
+    }
+    let (rest, _) = preceded(space0, tag(")"))(rest)?;
+    let (rest, return_type) = opt(preceded(
+        tuple((space0, tag(":"), space0)),
+        take_till1(|c: char| c.is_whitespace() || c == '['),
+    ))(rest)?;
+    let (rest, link) = opt(preceded(space0, parse_hyperlink))(rest)?;
+
+    Ok((
+        rest,
+        PlantUMLMethod {
+            accessibility,
+            modifier,
+            return_type: return_type.unwrap_or_default(),
+            name,
+            arguments,
+            link,
+            span: Span::between(element, trimmed, rest),
         },
     ))
 }
@@ -78,6 +198,7 @@ pub fn parse_method(element: &str) -> IResult<&str, PlantUMLMethod> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::spans::Span;
     //METHOD PARSER
     #[test]
     fn parse_method_without_parameters() {
@@ -89,8 +210,10 @@ mod tests {
                 name: "setNombreEquipo",
                 accessibility: Accessibility::Public,
                 modifier: Modifier::None,
+                link: None,
                 return_type: "void",
-                arguments: vec![]
+                arguments: vec![],
+                span: Span::new(1, 25)
             }
         );
     }
@@ -107,11 +230,14 @@ mod tests {
                 name: "setNombreEquipo",
                 accessibility: Accessibility::Public,
                 modifier: Modifier::None,
+                link: None,
                 return_type: "void",
                 arguments: vec![MethodArgument {
                     name: "nombre",
-                    argument_type: "String"
-                }]
+                    argument_type: "String",
+                    span: Span::new(24, 37)
+                }],
+                span: Span::new(1, 38)
             }
         );
     }
@@ -128,11 +254,14 @@ mod tests {
                 name: "setNombreEquipo",
                 accessibility: Accessibility::Public,
                 modifier: Modifier::Abstract,
+                link: None,
                 return_type: "void",
                 arguments: vec![MethodArgument {
                     name: "nombre",
-                    argument_type: "String"
-                }]
+                    argument_type: "String",
+                    span: Span::new(35, 48)
+                }],
+                span: Span::new(1, 49)
             }
         );
     }
@@ -149,11 +278,14 @@ mod tests {
                 name: "NombreEquipo",
                 accessibility: Accessibility::Public,
                 modifier: Modifier::Constructor,
+                link: None,
                 return_type: "",
                 arguments: vec![MethodArgument {
                     name: "nombre",
-                    argument_type: "String"
-                }]
+                    argument_type: "String",
+                    span: Span::new(23, 36)
+                }],
+                span: Span::new(1, 37)
             }
         );
     }
@@ -170,17 +302,21 @@ mod tests {
                 name: "setNombreEquipo",
                 accessibility: Accessibility::Public,
                 modifier: Modifier::Abstract,
+                link: None,
                 return_type: "void",
                 arguments: vec![
                     MethodArgument {
                         name: "nombre",
-                        argument_type: "String"
+                        argument_type: "String",
+                        span: Span::new(35, 48)
                     },
                     MethodArgument {
                         name: "posicion",
-                        argument_type: "int"
+                        argument_type: "int",
+                        span: Span::new(50, 62)
                     }
-                ]
+                ],
+                span: Span::new(1, 63)
             }
         );
     }
@@ -193,6 +329,16 @@ mod tests {
         assert!(output.is_err());
     }
 
+    #[test]
+    fn parse_method_with_bad_arguments_fails() {
+        assert!(parse_method("+ void jugar(ñ)\n").is_err());
+        assert!(parse_method("+ void jugar(String ñandú\n").is_err());
+        assert!(parse_method("+ void jugar(String nombre\n").is_err());
+        assert!(parse_method("+ void jugar(String nombre").is_err());
+        assert!(parse_method("+ void jugar\n").is_err());
+        assert!(parse_method("+ void ñandú\n").is_err());
+    }
+
     //PARSING METHOD ARGUMENTS
     #[test]
     fn parse_mthd_argument_paren() {
@@ -225,18 +371,34 @@ mod tests {
             output.1[0],
             MethodArgument {
                 name: "nombre_producto",
-                argument_type: "String"
+                argument_type: "String",
+                span: Span::new(0, 22)
             }
         );
         assert_eq!(
             output.1[1],
             MethodArgument {
                 name: "cantidad",
-                argument_type: "int"
+                argument_type: "int",
+                span: Span::new(24, 36)
             }
         );
     }
     #[test]
+    fn parse_method_with_hyperlink() {
+        let input = "+ void jugar() [[https://wiki/jugar Cómo se juega]]\n";
+        let (rest, output) = parse_method(input).unwrap();
+        assert_eq!(rest, "\n");
+        assert_eq!(
+            output.link,
+            Some(Hyperlink {
+                url: Some("https://wiki/jugar"),
+                tooltip: None,
+                label: Some("Cómo se juega"),
+            })
+        );
+    }
+    #[test]
     fn cant_parse_method_argument() {
         let input = "String nombre producto)"; //Parameter has spaces
         let output = parse_method_argument(input);
@@ -253,4 +415,46 @@ mod tests {
         let output = parse_method_argument(input);
         assert!(output.is_err(), "Parameter needs a type");
     }
+
+    #[test]
+    fn parse_uml_method_works() {
+        let input = "\t+ {static} setNombre(nombre: String, edad : int): void\n";
+        let (rest, output) = parse_uml_method(input).unwrap();
+        assert_eq!(rest, "\n");
+        assert_eq!(
+            output,
+            PlantUMLMethod {
+                name: "setNombre",
+                accessibility: Accessibility::Public,
+                modifier: Modifier::Static,
+                link: None,
+                return_type: "void",
+                arguments: vec![
+                    MethodArgument {
+                        name: "nombre",
+                        argument_type: "String",
+                        span: Span::new(22, 36)
+                    },
+                    MethodArgument {
+                        name: "edad",
+                        argument_type: "int",
+                        span: Span::new(38, 48)
+                    },
+                ],
+                span: Span::new(1, 55)
+            }
+        );
+
+        let (_, output) = parse_uml_method("+ {ctor} Equipo() [[https://wiki/Equipo]]\n").unwrap();
+        assert_eq!(output.return_type, "");
+        assert!(output.link.is_some());
+    }
+
+    #[test]
+    fn parse_uml_method_fails() {
+        assert!(parse_uml_method("+ void jugar()\n").is_err());
+        assert!(parse_uml_method("+ jugar(otro objeto: Object): void\n").is_err());
+        assert!(parse_uml_method("+ jugar(Object otro): void\n").is_err());
+        assert!(parse_uml_method("- nombre: String\n").is_err());
+    }
 }
@@ -1,9 +1,11 @@
-use nom::branch::alt;
 use nom::bytes::complete::tag;
-use nom::combinator::opt;
+use nom::error::Error;
+use nom::error::ErrorKind;
+use nom::error::ParseError;
+use nom::Err;
 use nom::IResult;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum Modifier {
     Abstract,
     Static,
@@ -12,23 +14,61 @@ pub enum Modifier {
     None,
 }
 
+/// The modifiers a member can have. `{field}` and `{method}` only say what kind of member it is, see
+/// [`member_kind`], and `{classifier}` is another way of writing `{static}`.
+pub const MODIFIERS: [&str; 7] = [
+    "{static}",
+    "{abstract}",
+    "{virtual}",
+    "{ctor}",
+    "{classifier}",
+    "{field}",
+    "{method}",
+];
+
+/// The kind of member that `{field}` and `{method}` say a member is.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MemberKind {
+    Field,
+    Method,
+}
+
+/// The kind of member a line of a body says it is with `{field}` or `{method}` after its visibility,
+/// the member is only parsed as that kind.
+pub fn member_kind(line: &str) -> Option<MemberKind> {
+    let member = line
+        .trim_start()
+        .trim_start_matches(['+', '-', '#', '~'])
+        .trim_start();
+    if member.starts_with("{field}") {
+        Some(MemberKind::Field)
+    } else if member.starts_with("{method}") {
+        Some(MemberKind::Method)
+    } else {
+        None
+    }
+}
+
+fn modifier_of(modifier: &str) -> Modifier {
+    match modifier {
+        "{static}" | "{classifier}" => Modifier::Static,
+        "{abstract}" => Modifier::Abstract,
+        "{virtual}" => Modifier::Virtual,
+        "{ctor}" => Modifier::Constructor,
+        _ => Modifier::None,
+    }
+}
+
+/// Parses an optional modifier, a modifier between braces that isn't a known one is an error.
 pub fn parse_modifier(element: &str) -> IResult<&str, Modifier> {
-    opt(alt((
-        tag("{static}"),
-        tag("{abstract}"),
-        tag("{virtual}"),
-        tag("{ctor}"),
-    )))(element)
-    .map(|(rest, simbol)| match simbol {
-        Some(s) => match s {
-            "{static}" => (rest, Modifier::Static),
-            "{abstract}" => (rest, Modifier::Abstract),
-            "{virtual}" => (rest, Modifier::Virtual),
-            "{ctor}" => (rest, Modifier::Constructor),
-            _ => (rest, Modifier::None),
-        },
-        None => (rest, Modifier::None),
-    })
+    if !element.starts_with('{') {
+        return Ok((element, Modifier::None));
+    }
+    MODIFIERS
+        .iter()
+        .find_map(|modifier| tag::<_, _, Error<&str>>(*modifier)(element).ok())
+        .map(|(rest, modifier)| (rest, modifier_of(modifier)))
+        .ok_or_else(|| Err::Error(Error::from_error_kind(element, ErrorKind::Tag)))
 }
 
 #[cfg(test)]
@@ -51,6 +91,9 @@ mod tests {
             ("{abstract}", Modifier::Abstract),
             ("{virtual}", Modifier::Virtual),
             ("{ctor}", Modifier::Constructor),
+            ("{classifier}", Modifier::Static),
+            ("{field}", Modifier::None),
+            ("{method}", Modifier::None),
         ]);
         for modifier in cases.keys() {
             let input = format!("{} nombreEquipo: String", modifier);
@@ -60,4 +103,29 @@ mod tests {
             assert_eq!(&parser_output.1, cases.get(modifier).unwrap());
         }
     }
+
+    #[test]
+    fn unknown_modifier_fails() {
+        assert!(parse_modifier("{final} void jugar()").is_err());
+        assert!(parse_modifier("{ctor} Jugador()").is_ok());
+        assert!(parse_modifier("{field} nombre: String").is_ok());
+    }
+
+    #[test]
+    fn member_kind_works() {
+        assert_eq!(
+            member_kind("  - {field} nombre: String"),
+            Some(MemberKind::Field)
+        );
+        assert_eq!(
+            member_kind("+ {method} jugar(): void"),
+            Some(MemberKind::Method)
+        );
+        assert_eq!(
+            member_kind("{method} jugar(): void"),
+            Some(MemberKind::Method)
+        );
+        assert_eq!(member_kind("+ {static} jugar(): void"), None);
+        assert_eq!(member_kind("- nombre: String"), None);
+    }
 }
@@ -0,0 +1,232 @@
+use crate::elements::line_terminator;
+use crate::elements::parse_block_lines;
+use crate::elements::parse_quoted;
+use crate::relationships::parse_reference;
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::character::complete::char;
+use nom::character::complete::not_line_ending;
+use nom::character::complete::space0;
+use nom::character::complete::space1;
+use nom::combinator::map;
+use nom::combinator::opt;
+use nom::combinator::value;
+use nom::error::Error;
+use nom::error::ErrorKind;
+use nom::error::ParseError;
+use nom::sequence::delimited;
+use nom::sequence::preceded;
+use nom::sequence::terminated;
+use nom::sequence::tuple;
+use nom::Err;
+use nom::IResult;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum NotePosition {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    /// The note isn't placed next to anything, it's the case of `note "texto" as N1` and of notes on
+    /// links without an explicit position.
+    None,
+}
+
+/// What a note is attached to.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum NoteTarget<'a> {
+    /// Reference to an element, resolve it with [`crate::PlantUMLFile::find_element`].
+    Element(&'a str),
+    /// A member of an element, like `Equipo::nombre`.
+    Member { element: &'a str, member: &'a str },
+    /// Index of the relationship in [`crate::PlantUMLFile::relationships`].
+    Relationship(usize),
+    /// A note that isn't attached to anything, it can still be linked to elements with `N1 .. Equipo`.
+    Floating,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PlantUMLNote<'a> {
+    /// Name given to the note with `as`, used to link floating notes.
+    pub alias: Option<&'a str>,
+    pub position: NotePosition,
+    pub target: NoteTarget<'a>,
+    /// Lines of text of the note, without indentation.
+    pub text: Vec<&'a str>,
+    /// References of the elements a floating note is linked to.
+    pub links: Vec<&'a str>,
+}
+
+fn parse_position(element: &str) -> IResult<&str, NotePosition> {
+    alt((
+        value(NotePosition::Left, tag("left")),
+        value(NotePosition::Right, tag("right")),
+        value(NotePosition::Top, tag("top")),
+        value(NotePosition::Bottom, tag("bottom")),
+    ))(element)
+}
+
+/// Parses the text of a note, either on the same line after a colon or on the following lines until
+/// `end note` is found.
+fn parse_note_text(element: &str) -> IResult<&str, Vec<&str>> {
+    if let Ok((rest, text)) = delimited(
+        tuple((space0, char::<_, Error<&str>>(':'), space0)),
+        not_line_ending,
+        line_terminator,
+    )(element)
+    {
+        return Ok((rest, vec![text.trim()]));
+    }
+
+    let (rest, _) = preceded(space0, line_terminator)(element)?;
+    parse_block_lines(rest, "note")
+}
+
+/// Parses a note. `last_relationship` is the index of the relationship a `note on link` is attached
+/// to, notes on links can't be parsed if it's `None`.
+pub fn parse_note(
+    element: &str, last_relationship: Option<usize>,
+) -> IResult<&str, PlantUMLNote<'_>> {
+    let (rest, _) = terminated(tag("note"), space1)(element.trim_start())?;
+
+    // note "texto" as N1
+    if let Ok((rest, (text, alias))) = tuple((
+        parse_quoted,
+        opt(preceded(
+            tuple((space1, tag("as"), space1)),
+            parse_reference,
+        )),
+    ))(rest)
+    {
+        let (rest, _) = preceded(space0, line_terminator)(rest)?;
+        let note = PlantUMLNote {
+            alias,
+            position: NotePosition::None,
+            target: NoteTarget::Floating,
+            text: vec![text],
+            links: vec![],
+        };
+        return Ok((rest, note));
+    }
+
+    // note as N1 ... end note
+    if let Ok((rest, alias)) = preceded(
+        tuple((tag::<_, _, Error<&str>>("as"), space1)),
+        parse_reference,
+    )(rest)
+    {
+        let (rest, text) = parse_note_text(rest)?;
+        let note = PlantUMLNote {
+            alias: Some(alias),
+            position: NotePosition::None,
+            target: NoteTarget::Floating,
+            text,
+            links: vec![],
+        };
+        return Ok((rest, note));
+    }
+
+    let (rest, position) = opt(terminated(parse_position, space1))(rest)?;
+    let (rest, target) = alt((
+        value(None, tag("on link")),
+        map(preceded(tuple((tag("of"), space1)), parse_reference), Some),
+    ))(rest)?;
+    let target = match (target, last_relationship) {
+        (Some(reference), _) => NoteTarget::Element(reference),
+        (None, Some(relationship)) => NoteTarget::Relationship(relationship),
+        (None, None) => return Err(Err::Error(Error::from_error_kind(rest, ErrorKind::Verify))),
+    };
+    let (rest, text) = parse_note_text(rest)?;
+
+    Ok((
+        rest,
+        PlantUMLNote {
+            alias: None,
+            position: position.unwrap_or(NotePosition::None),
+            target,
+            text,
+            links: vec![],
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_single_line_note_works() {
+        let (rest, note) = parse_note(
+            "note left of Equipo : no puede tener más de 11 jugadores\n",
+            None,
+        )
+        .expect("Couldn't parse the note!");
+        assert_eq!("", rest);
+        assert_eq!(
+            note,
+            PlantUMLNote {
+                alias: None,
+                position: NotePosition::Left,
+                target: NoteTarget::Element("Equipo"),
+                text: vec!["no puede tener más de 11 jugadores"],
+                links: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn parse_multi_line_note_works() {
+        let input = "note right of Equipo::nombre
+    El nombre es único
+    dentro del torneo
+end note
+class Equipo";
+        let (rest, note) = parse_note(input, None).expect("Couldn't parse the note!");
+        assert_eq!("class Equipo", rest);
+        assert_eq!(
+            note,
+            PlantUMLNote {
+                alias: None,
+                position: NotePosition::Right,
+                target: NoteTarget::Element("Equipo::nombre"),
+                text: vec!["El nombre es único", "dentro del torneo"],
+                links: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn parse_floating_note_works() {
+        let (_, note) = parse_note("note \"Regla de negocio\" as N1\n", None)
+            .expect("Couldn't parse the note!");
+        assert_eq!(note.alias, Some("N1"));
+        assert_eq!(note.target, NoteTarget::Floating);
+        assert_eq!(note.text, vec!["Regla de negocio"]);
+
+        let (_, note) = parse_note("note as N2\n  primera\n  segunda\nendnote", None)
+            .expect("Couldn't parse the note!");
+        assert_eq!(note.alias, Some("N2"));
+        assert_eq!(note.position, NotePosition::None);
+        assert_eq!(note.text, vec!["primera", "segunda"]);
+    }
+
+    #[test]
+    fn parse_note_on_link_works() {
+        let (_, note) = parse_note("note on link : se juega cada semana\n", Some(3))
+            .expect("Couldn't parse the note!");
+        assert_eq!(note.target, NoteTarget::Relationship(3));
+        assert_eq!(note.position, NotePosition::None);
+
+        let (_, note) = parse_note("note bottom on link\n  texto\nend note\n", Some(0))
+            .expect("Couldn't parse the note!");
+        assert_eq!(note.target, NoteTarget::Relationship(0));
+        assert_eq!(note.position, NotePosition::Bottom);
+    }
+
+    #[test]
+    fn parse_note_fails() {
+        assert!(parse_note("note on link : sin relación\n", None).is_err());
+        assert!(parse_note("note left of Equipo\n  sin final\n", None).is_err());
+        assert!(parse_note("note left Equipo : texto\n", None).is_err());
+    }
+}
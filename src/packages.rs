@@ -0,0 +1,146 @@
+use crate::elements::parse_body_opening;
+use crate::elements::parse_element_name;
+use crate::elements::parse_header_decorations;
+use crate::elements::ElementName;
+use crate::styles::ElementStyle;
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::character::complete::not_line_ending;
+use nom::character::complete::space1;
+use nom::sequence::preceded;
+use nom::sequence::tuple;
+use nom::IResult;
+
+/// A package (or namespace), either declared with `package` or created implicitly by a qualified name
+/// like `class com.acme.Equipo`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PlantUMLPackage<'a> {
+    pub name: &'a str,
+    pub display_name: &'a str,
+    pub namespace: Vec<&'a str>,
+    pub style: Option<ElementStyle<'a>>,
+}
+
+impl<'a> PlantUMLPackage<'a> {
+    /// Namespace of the elements declared inside this package.
+    pub fn path(&self) -> Vec<&'a str> {
+        let mut path = self.namespace.clone();
+        path.push(self.name);
+        path
+    }
+}
+
+/// Parses the header of a package declared with `package` or `namespace`.
+///
+/// The boolean is `true` when the package opens a body, the elements that follow belong to the package
+/// until its closing brace is found.
+pub fn parse_package_with_separator<'a>(
+    element: &'a str, separator: Option<&str>,
+) -> IResult<&'a str, (PlantUMLPackage<'a>, bool)> {
+    let (rest, name) = alt((
+        parse_element_name("package"),
+        parse_element_name("namespace"),
+    ))(element.trim_start())?;
+    let (rest, decorations) = parse_header_decorations(rest)?;
+    let (rest, has_body) = parse_body_opening(rest)?;
+    let (namespace, name) = name.qualify(separator);
+    let ElementName {
+        name, display_name, ..
+    } = name;
+
+    Ok((
+        rest,
+        (
+            PlantUMLPackage {
+                name,
+                display_name,
+                namespace,
+                style: decorations.style,
+            },
+            has_body,
+        ),
+    ))
+}
+
+/// Parses the `set namespaceSeparator` directive, `none` disables splitting names into namespaces.
+pub fn parse_namespace_separator(element: &str) -> IResult<&str, Option<&str>> {
+    let (rest, separator) = preceded(
+        tuple((tag("set"), space1, tag("namespaceSeparator"), space1)),
+        not_line_ending,
+    )(element.trim_start())?;
+
+    match separator.trim() {
+        "none" => Ok((rest, None)),
+        separator => Ok((rest, Some(separator))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::styles::Background;
+
+    #[test]
+    fn parse_package_works() {
+        let (rest, (package, has_body)) =
+            parse_package_with_separator("package com.acme {\n", Some("."))
+                .expect("Couldn't parse the package!");
+        assert_eq!("\n", rest);
+        assert!(has_body);
+        assert_eq!(
+            package,
+            PlantUMLPackage {
+                name: "acme",
+                display_name: "acme",
+                namespace: vec!["com"],
+                style: None,
+            }
+        );
+        assert_eq!(package.path(), vec!["com", "acme"]);
+
+        let (_, (package, has_body)) =
+            parse_package_with_separator("namespace \"Modelo\" as modelo\n", Some("."))
+                .expect("Couldn't parse the package!");
+        assert!(!has_body);
+        assert_eq!(
+            package,
+            PlantUMLPackage {
+                name: "modelo",
+                display_name: "Modelo",
+                namespace: vec![],
+                style: None,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_package_with_style_works() {
+        let (_, (package, has_body)) =
+            parse_package_with_separator("package modelo #DDDDDD {\n", Some("."))
+                .expect("Couldn't parse the package!");
+        assert!(has_body);
+        assert_eq!(
+            package.style.and_then(|style| style.background),
+            Some(Background::Color("DDDDDD"))
+        );
+    }
+
+    #[test]
+    fn parse_package_fails() {
+        assert!(parse_package_with_separator("package Modelo Equipos {\n", Some(".")).is_err());
+        assert!(parse_package_with_separator("packages Modelo {\n", Some(".")).is_err());
+    }
+
+    #[test]
+    fn parse_namespace_separator_works() {
+        assert_eq!(
+            parse_namespace_separator("set namespaceSeparator ::\n"),
+            Ok(("\n", Some("::")))
+        );
+        assert_eq!(
+            parse_namespace_separator("set namespaceSeparator none"),
+            Ok(("", None))
+        );
+        assert!(parse_namespace_separator("set namespaceSeparator\n").is_err());
+    }
+}
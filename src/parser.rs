@@ -0,0 +1,523 @@
+use crate::dialects::MemberDialect;
+use crate::dialects::DEFAULT_DIALECTS;
+use crate::elements::DEFAULT_NAMESPACE_SEPARATOR;
+use crate::errors::ElementKind;
+use crate::errors::ParseError;
+use crate::errors::ParseErrorKind;
+use crate::errors::ParseWarning;
+use crate::parse_contents;
+use crate::sources::FileSystemResolver;
+use crate::sources::IncludeResolver;
+use crate::sources::Source;
+use crate::sources::Sources;
+use crate::sources::DIAGRAM_EXTENSIONS;
+use crate::PlantUMLFile;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::io::Read;
+use std::path::Path;
+
+/// Includes nested deeper than this are reported instead of expanded, unless the options say
+/// otherwise.
+pub const DEFAULT_MAX_INCLUDE_DEPTH: usize = 8;
+
+/// How a [`Parser`] reads diagrams. The default options are the ones of
+/// [`crate::parse_uml_with_warnings`].
+pub struct ParserOptions {
+    /// Turns every warning into an error, so nothing in the diagram can be ignored. References to
+    /// undeclared elements stay warnings, they're valid PlantUML.
+    pub strict: bool,
+    /// Syntaxes accepted for the members of classes and interfaces, in the order they're tried.
+    pub dialects: Vec<MemberDialect>,
+    /// Whether [`Parser::load`] expands the `!include` lines with `include_resolver`.
+    pub preprocess: bool,
+    /// Finds the included files. Without one, the files read from disk use [`FileSystemResolver`] and
+    /// the `!include` lines of other diagrams are reported as unsupported.
+    pub include_resolver: Option<Box<dyn IncludeResolver>>,
+    /// How many includes can be nested, which stops files that include themselves.
+    pub max_include_depth: usize,
+    /// Separator of qualified names until the diagram sets one with `set namespaceSeparator`.
+    pub namespace_separator: Option<&'static str>,
+    /// Whether comments are kept in [`PlantUMLFile::comments`].
+    pub retain_trivia: bool,
+}
+
+impl Default for ParserOptions {
+    fn default() -> Self {
+        ParserOptions {
+            strict: false,
+            dialects: DEFAULT_DIALECTS.to_vec(),
+            preprocess: true,
+            include_resolver: None,
+            max_include_depth: DEFAULT_MAX_INCLUDE_DEPTH,
+            namespace_separator: Some(DEFAULT_NAMESPACE_SEPARATOR),
+            retain_trivia: false,
+        }
+    }
+}
+
+impl fmt::Debug for ParserOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ParserOptions")
+            .field("strict", &self.strict)
+            .field("dialects", &self.dialects)
+            .field("preprocess", &self.preprocess)
+            .field("include_resolver", &self.include_resolver.is_some())
+            .field("max_include_depth", &self.max_include_depth)
+            .field("namespace_separator", &self.namespace_separator)
+            .field("retain_trivia", &self.retain_trivia)
+            .finish()
+    }
+}
+
+/// Everything a [`Parser`] found in a diagram. Elements and members with errors are left out of the
+/// file, the rest of the diagram is kept.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParseOutput<'a> {
+    pub file: PlantUMLFile<'a>,
+    pub errors: Vec<ParseError<'a>>,
+    pub warnings: Vec<ParseWarning<'a>>,
+}
+
+impl<'a> ParseOutput<'a> {
+    /// The file and its warnings when there are no errors.
+    pub fn into_result(
+        self,
+    ) -> Result<(PlantUMLFile<'a>, Vec<ParseWarning<'a>>), Vec<ParseError<'a>>> {
+        if self.errors.is_empty() {
+            Ok((self.file, self.warnings))
+        } else {
+            Err(self.errors)
+        }
+    }
+}
+
+/// Parses class diagrams with the given [`ParserOptions`]:
+///
+/// ```rust
+/// use plantuml_class_diagram_parser::dialects::MemberDialect;
+/// use plantuml_class_diagram_parser::parser::Parser;
+/// use plantuml_class_diagram_parser::parser::ParserOptions;
+///
+/// let parser = Parser::new(ParserOptions {
+///     strict: true,
+///     dialects: vec![MemberDialect::Uml],
+///     ..ParserOptions::default()
+/// });
+/// let source = parser.load("equipo.puml", "class Equipo {\n  + jugar(minutos: int): void\n}\n");
+/// let (file, _) = parser.parse(&source).into_result().expect("PlantUML couldn't be parsed!");
+/// assert_eq!(file.classes[0].methods[0].name, "jugar");
+/// ```
+#[derive(Debug, Default)]
+pub struct Parser {
+    options: ParserOptions,
+}
+
+impl Parser {
+    pub fn new(options: ParserOptions) -> Self {
+        Parser { options }
+    }
+
+    pub fn options(&self) -> &ParserOptions {
+        &self.options
+    }
+
+    /// Reads a diagram named `name`, expanding its includes when the options say so.
+    pub fn load(&self, name: impl Into<String>, contents: impl Into<String>) -> Source {
+        self.load_with(name, contents, None)
+    }
+
+    /// Reads a diagram from `reader`, `name` is the one shown in diagnostics.
+    pub fn read(&self, name: impl Into<String>, mut reader: impl Read) -> io::Result<Source> {
+        let mut contents = String::new();
+        reader.read_to_string(&mut contents)?;
+        Ok(self.load(name, contents))
+    }
+
+    /// Reads the diagram of a file, named by its path in diagnostics.
+    pub fn read_file(&self, path: impl AsRef<Path>) -> io::Result<Source> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path)?;
+        Ok(self.load_with(path.to_string_lossy(), contents, Some(&FileSystemResolver)))
+    }
+
+    /// Reads every diagram of a directory and its subdirectories, the files whose extension is one
+    /// of [`DIAGRAM_EXTENSIONS`], in any case. A file or subdirectory that can't be read doesn't
+    /// stop the rest, its error is kept under its path. When listing a subdirectory fails halfway,
+    /// the error is kept under the path of the subdirectory and the entries that weren't listed yet
+    /// are skipped, so each directory has at most one error. Symbolic links to directories aren't
+    /// followed.
+    pub fn read_dir(&self, path: impl AsRef<Path>) -> io::Result<Sources> {
+        let mut sources = Sources::new();
+        let root = path.as_ref().to_path_buf();
+        let mut directories = vec![root.clone()];
+        while let Some(directory) = directories.pop() {
+            let entries = match fs::read_dir(&directory) {
+                Ok(entries) => entries,
+                Err(e) if directory == root => return Err(e),
+                Err(e) => {
+                    sources.insert(directory, Err(e));
+                    continue;
+                }
+            };
+            for entry in entries {
+                let entry = match entry {
+                    Ok(entry) => entry,
+                    Err(e) => {
+                        sources.insert(directory.clone(), Err(e));
+                        break;
+                    }
+                };
+                let path = entry.path();
+                match entry.file_type() {
+                    Ok(file_type) if file_type.is_dir() => directories.push(path),
+                    Ok(_) if is_diagram(&path) => {
+                        let source = self.read_file(&path);
+                        sources.insert(path, source);
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        sources.insert(path, Err(e));
+                    }
+                }
+            }
+        }
+        Ok(sources)
+    }
+
+    fn load_with(
+        &self, name: impl Into<String>, contents: impl Into<String>,
+        default_resolver: Option<&dyn IncludeResolver>,
+    ) -> Source {
+        let resolver = self
+            .options
+            .include_resolver
+            .as_deref()
+            .or(default_resolver);
+        match resolver {
+            Some(resolver) if self.options.preprocess => {
+                Source::with_includes(name, contents, resolver, self.options.max_include_depth)
+            }
+            _ => Source::new(name, contents),
+        }
+    }
+
+    /// Parses a loaded diagram. The spans of the errors are relative to [`Source::text`], see
+    /// [`Source::relocate`] to find the file they were found in.
+    pub fn parse<'a>(&self, source: &'a Source) -> ParseOutput<'a> {
+        let text = source.text();
+        let (_, file, errors, mut warnings) = parse_contents(text, &self.options);
+        for &(span, kind) in source.include_errors() {
+            let unsupported = warnings.iter_mut().find(|warning| {
+                warning.span.start == span.start
+                    && warning.kind == ParseErrorKind::UnsupportedPreprocessor
+            });
+            match unsupported {
+                Some(warning) => warning.kind = kind,
+                None => warnings.push(ParseWarning::new(
+                    text,
+                    kind,
+                    ElementKind::Diagram,
+                    &text[span.start..span.end],
+                    None,
+                )),
+            }
+        }
+        warnings.sort_by_key(|warning| warning.span.start);
+        self.output(file, errors, warnings)
+    }
+
+    /// Parses every diagram read by [`Parser::read_dir`], keyed by their path. The ones that couldn't
+    /// be read keep their error.
+    pub fn parse_all<'a>(
+        &self, sources: &'a Sources,
+    ) -> BTreeMap<&'a Path, Result<ParseOutput<'a>, &'a io::Error>> {
+        sources
+            .iter()
+            .map(|(path, source)| {
+                let output = source.as_ref().map(|source| self.parse(source));
+                (path.as_path(), output)
+            })
+            .collect()
+    }
+
+    /// Parses a diagram that is already in memory, its includes aren't expanded.
+    pub fn parse_str<'a>(&self, content: &'a str) -> ParseOutput<'a> {
+        let (_, file, errors, warnings) = parse_contents(content, &self.options);
+        self.output(file, errors, warnings)
+    }
+
+    fn output<'a>(
+        &self, file: PlantUMLFile<'a>, mut errors: Vec<ParseError<'a>>,
+        mut warnings: Vec<ParseWarning<'a>>,
+    ) -> ParseOutput<'a> {
+        if self.options.strict {
+            let (promoted, kept): (Vec<_>, Vec<_>) = warnings
+                .into_iter()
+                .partition(|warning| warning.kind.is_strict_error());
+            errors.extend(promoted);
+            errors.sort_by_key(|error| error.span.start);
+            warnings = kept;
+        }
+        ParseOutput {
+            file,
+            errors,
+            warnings,
+        }
+    }
+}
+
+/// Checks if the extension of a file is one of [`DIAGRAM_EXTENSIONS`], ignoring its case.
+fn is_diagram(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            DIAGRAM_EXTENSIONS
+                .iter()
+                .any(|diagram| extension.eq_ignore_ascii_case(diagram))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialects::ALL_DIALECTS;
+    use crate::sources::SourceFile;
+    use crate::spans::Span;
+    use std::env;
+    use std::path::PathBuf;
+
+    /// A new empty directory for the files of a test.
+    fn test_directory(name: &str) -> PathBuf {
+        let directory = env::temp_dir().join(format!("puml-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).expect("Couldn't create the directory!");
+        directory
+    }
+
+    const DIAGRAM: &str = "' Modelo de equipos
+class Equipo {
+  - String nombre
+  + jugar(minutos: int): void
+}
+clas Jugador
+";
+
+    #[test]
+    fn parse_with_default_options_works() {
+        let output = Parser::default().parse_str(DIAGRAM);
+        let errors: Vec<_> = output.errors.iter().map(|error| error.text).collect();
+        assert_eq!(errors, ["- String nombre", "+ jugar(minutos: int): void"]);
+        assert_eq!(output.file.classes[0].name, "Equipo");
+        assert_eq!(output.warnings[0].kind, ParseErrorKind::UnrecognizedLine);
+        assert!(output.file.comments.is_empty());
+    }
+
+    #[test]
+    fn parse_with_dialects_works() {
+        let parser = Parser::new(ParserOptions {
+            dialects: vec![MemberDialect::Java],
+            ..ParserOptions::default()
+        });
+        let output = parser.parse_str(DIAGRAM);
+        assert_eq!(output.errors.len(), 1);
+        assert_eq!(output.errors[0].text, "+ jugar(minutos: int): void");
+        assert_eq!(output.file.classes[0].fields.len(), 1);
+        assert!(output.file.classes[0].methods.is_empty());
+    }
+
+    #[test]
+    fn parse_strict_works() {
+        let parser = Parser::new(ParserOptions {
+            strict: true,
+            dialects: ALL_DIALECTS.to_vec(),
+            ..ParserOptions::default()
+        });
+        let errors = parser
+            .parse_str(DIAGRAM)
+            .into_result()
+            .expect_err("PlantUML shouldn't be parsed!");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, ParseErrorKind::UnrecognizedLine);
+        assert_eq!(errors[0].text, "clas Jugador");
+    }
+
+    #[test]
+    fn parse_strict_keeps_unresolved_references_as_warnings() {
+        let parser = Parser::new(ParserOptions {
+            strict: true,
+            ..ParserOptions::default()
+        });
+        let output = parser.parse_str("class Jugador\nJugadr --> Jugador\nJugador --> C\n");
+        assert!(output.errors.is_empty());
+        let warnings: Vec<_> = output
+            .warnings
+            .iter()
+            .map(|warning| (warning.kind, warning.text))
+            .collect();
+        assert_eq!(warnings, [(ParseErrorKind::UnresolvedReference, "Jugadr")]);
+    }
+
+    #[test]
+    fn parse_with_trivia_and_separator_works() {
+        let parser = Parser::new(ParserOptions {
+            retain_trivia: true,
+            namespace_separator: Some("::"),
+            ..ParserOptions::default()
+        });
+        let output = parser.parse_str("' Modelo\nclass modelo::Equipo\n/' fin '/\n");
+        let comments: Vec<_> = output
+            .file
+            .comments
+            .iter()
+            .map(|comment| (comment.text, comment.span))
+            .collect();
+        assert_eq!(
+            comments,
+            vec![(" Modelo", Span::new(0, 8)), (" fin ", Span::new(30, 39))]
+        );
+        assert_eq!(output.file.classes[0].namespace, vec!["modelo"]);
+        assert_eq!(output.file.namespace_separator, Some("::"));
+    }
+
+    #[test]
+    fn parse_with_includes_works() {
+        let resolver = |path: &str, _: &str| match path {
+            "jugador.iuml" => Some(SourceFile::new("jugador.iuml", "class Jugador\n")),
+            _ => None,
+        };
+        let contents = "!include jugador.iuml\n!include falta.iuml\nclass Equipo\n";
+        let parser = Parser::new(ParserOptions {
+            include_resolver: Some(Box::new(resolver)),
+            ..ParserOptions::default()
+        });
+        let source = parser.load("equipo.puml", contents);
+        let output = parser.parse(&source);
+        assert_eq!(output.file.classes.len(), 2);
+        let warnings: Vec<_> = output
+            .warnings
+            .iter()
+            .map(|warning| (warning.kind, warning.text))
+            .collect();
+        assert_eq!(
+            warnings,
+            vec![(ParseErrorKind::UnresolvedInclude, "!include falta.iuml")]
+        );
+        let (file, warning) = source.relocate(&output.warnings[0]);
+        assert_eq!(file.name, "equipo.puml");
+        assert_eq!(warning.line, 2);
+
+        let parser = Parser::new(ParserOptions {
+            preprocess: false,
+            include_resolver: Some(Box::new(resolver)),
+            ..ParserOptions::default()
+        });
+        let source = parser.load("equipo.puml", contents);
+        let output = parser.parse(&source);
+        assert_eq!(output.file.classes.len(), 1);
+        assert_eq!(
+            output.warnings[0].kind,
+            ParseErrorKind::UnsupportedPreprocessor
+        );
+    }
+
+    #[test]
+    fn read_works() {
+        let parser = Parser::default();
+        let source = parser
+            .read(
+                "equipo.puml",
+                "class Equipo\n!include jugador.iuml\n".as_bytes(),
+            )
+            .expect("Couldn't read the diagram!");
+        assert_eq!(source.name(), "equipo.puml");
+        let output = parser.parse(&source);
+        assert_eq!(output.file.classes[0].name, "Equipo");
+        assert_eq!(
+            output.warnings[0].kind,
+            ParseErrorKind::UnsupportedPreprocessor
+        );
+    }
+
+    #[test]
+    fn read_file_works() {
+        let directory = test_directory("read-file");
+        fs::write(
+            directory.join("equipo.puml"),
+            "class Equipo\n!include jugador.iuml\n",
+        )
+        .unwrap();
+        fs::write(directory.join("jugador.iuml"), "class Jugador\n").unwrap();
+
+        let parser = Parser::default();
+        let source = parser
+            .read_file(directory.join("equipo.puml"))
+            .expect("Couldn't read the diagram!");
+        let output = parser.parse(&source);
+        assert!(output.warnings.is_empty());
+        assert_eq!(output.file.classes[1].name, "Jugador");
+        assert_eq!(
+            source.files()[1].name,
+            directory.join("jugador.iuml").to_string_lossy()
+        );
+        assert!(parser.read_file(directory.join("falta.puml")).is_err());
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn is_diagram_works() {
+        assert!(is_diagram(Path::new("modelo/equipo.puml")));
+        assert!(is_diagram(Path::new("Equipo.PUML")));
+        assert!(is_diagram(Path::new("x.PlantUML")));
+        assert!(!is_diagram(Path::new("notas.txt")));
+        assert!(!is_diagram(Path::new("puml")));
+    }
+
+    #[test]
+    fn read_dir_works() {
+        let directory = test_directory("read-dir");
+        fs::create_dir(directory.join("modelo")).unwrap();
+        fs::write(directory.join("equipo.puml"), "class Equipo\n").unwrap();
+        fs::write(directory.join("modelo/jugador.plantuml"), "clas Jugador\n").unwrap();
+        fs::write(directory.join("modelo/color.iuml"), "enum Color\n").unwrap();
+        fs::write(directory.join("modelo/roto.puml"), [0xff, 0xfe, b'\n']).unwrap();
+        fs::write(directory.join("notas.txt"), "class Nota\n").unwrap();
+        fs::write(directory.join("Partido.PUML"), "class Partido\n").unwrap();
+        fs::write(directory.join("modelo/torneo.PlantUML"), "class Torneo\n").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&directory, directory.join("modelo/bucle")).unwrap();
+
+        let parser = Parser::default();
+        let sources = parser
+            .read_dir(&directory)
+            .expect("Couldn't read the diagrams!");
+        let outputs = parser.parse_all(&sources);
+        let names: Vec<_> = outputs
+            .iter()
+            .map(|(path, output)| {
+                let warnings = output.as_ref().map(|output| output.warnings.len());
+                let error = output.as_ref().err().map(|e| e.kind());
+                (path.strip_prefix(&directory).unwrap(), warnings.ok(), error)
+            })
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                (Path::new("Partido.PUML"), Some(0), None),
+                (Path::new("equipo.puml"), Some(0), None),
+                (Path::new("modelo/color.iuml"), Some(0), None),
+                (Path::new("modelo/jugador.plantuml"), Some(1), None),
+                (
+                    Path::new("modelo/roto.puml"),
+                    None,
+                    Some(io::ErrorKind::InvalidData)
+                ),
+                (Path::new("modelo/torneo.PlantUML"), Some(0), None),
+            ]
+        );
+        assert!(parser.read_dir(directory.join("falta")).is_err());
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
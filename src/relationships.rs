@@ -0,0 +1,652 @@
+use crate::elements::line_terminator;
+use crate::elements::parse_quoted;
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::bytes::complete::take_till;
+use nom::bytes::complete::take_while1;
+use nom::character::complete::char;
+use nom::character::complete::not_line_ending;
+use nom::character::complete::space0;
+use nom::character::complete::space1;
+use nom::combinator::map;
+use nom::combinator::opt;
+use nom::combinator::peek;
+use nom::combinator::recognize;
+use nom::combinator::value;
+use nom::multi::separated_list1;
+use nom::sequence::delimited;
+use nom::sequence::pair;
+use nom::sequence::preceded;
+use nom::sequence::separated_pair;
+use nom::sequence::terminated;
+use nom::sequence::tuple;
+use nom::IResult;
+
+/// The symbol drawn at one of the ends of a relationship.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ArrowHead {
+    /// `<|` or `|>`
+    Extension,
+    /// `*`
+    Composition,
+    /// `o`
+    Aggregation,
+    /// `<` or `>`
+    Arrow,
+    /// `+`, drawn on the element that contains the other one
+    Nested,
+    /// `()`, drawn on the class that provides or requires the interface at the other end
+    Lollipop,
+    None,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum LineStyle {
+    /// `--`
+    Solid,
+    /// `..`
+    Dotted,
+}
+
+/// Style of the line given between brackets, like `-[dashed]->`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Stroke {
+    Dashed,
+    Dotted,
+    Bold,
+    Plain,
+}
+
+/// Layout hint given inside the arrow, like `-up->` or `-l->`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// What a relationship means, derived from its arrow heads and line style.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RelationshipKind {
+    Extension,
+    Implementation,
+    Composition,
+    Aggregation,
+    Dependency,
+    Association,
+    /// One of the elements is declared inside the other one (`--+`).
+    Nesting,
+    /// A class requires an interface, drawn with the lollipop notation (`IBar -() Foo`).
+    Usage,
+}
+
+/// A relationship between two elements like `Equipo "1" *-- "many" Jugador : tiene`.
+///
+/// `from` and `to` are the references exactly as they were written, they can be either the name or the
+/// display name of an element. Use [`crate::PlantUMLFile::find_element`] to resolve them.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PlantUMLRelationship<'a> {
+    pub from: &'a str,
+    pub to: &'a str,
+    pub from_head: ArrowHead,
+    pub to_head: ArrowHead,
+    pub line: LineStyle,
+    pub from_cardinality: Option<&'a str>,
+    pub to_cardinality: Option<&'a str>,
+    /// Qualifiers of a qualified association, like `key: String` in `Map [key: String] --> Valor`.
+    pub from_qualifiers: Vec<&'a str>,
+    pub to_qualifiers: Vec<&'a str>,
+    pub label: Option<&'a str>,
+    /// Color given inside the arrow, like `#red` in `-[#red]->`.
+    pub color: Option<&'a str>,
+    pub stroke: Option<Stroke>,
+    pub direction: Option<Direction>,
+    /// The relationship is only used for the layout and isn't drawn (`-[hidden]-`).
+    pub hidden: bool,
+}
+
+impl<'a> PlantUMLRelationship<'a> {
+    /// Returns the references of the outer and the inner element when the relationship is a nesting
+    /// one, like `Interna --+ Externa`.
+    pub fn nesting(&self) -> Option<(&'a str, &'a str)> {
+        if self.to_head == ArrowHead::Nested {
+            Some((self.to, self.from))
+        } else if self.from_head == ArrowHead::Nested {
+            Some((self.from, self.to))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the references of the class and the interface of a relationship written with the
+    /// lollipop notation.
+    ///
+    /// The `()` is drawn next to the class. When the interface is written after it (`Foo ()- IBar`) the
+    /// class provides the interface, when it's written before it (`IBar -() Foo`) the class requires it.
+    pub fn lollipop(&self) -> Option<(&'a str, &'a str)> {
+        if self.from_head == ArrowHead::Lollipop {
+            Some((self.from, self.to))
+        } else if self.to_head == ArrowHead::Lollipop {
+            Some((self.to, self.from))
+        } else {
+            None
+        }
+    }
+
+    pub fn kind(&self) -> RelationshipKind {
+        let heads = [self.from_head, self.to_head];
+        if self.from_head == ArrowHead::Lollipop {
+            RelationshipKind::Implementation
+        } else if self.to_head == ArrowHead::Lollipop {
+            RelationshipKind::Usage
+        } else if heads.contains(&ArrowHead::Nested) {
+            RelationshipKind::Nesting
+        } else if heads.contains(&ArrowHead::Extension) {
+            match self.line {
+                LineStyle::Solid => RelationshipKind::Extension,
+                LineStyle::Dotted => RelationshipKind::Implementation,
+            }
+        } else if heads.contains(&ArrowHead::Composition) {
+            RelationshipKind::Composition
+        } else if heads.contains(&ArrowHead::Aggregation) {
+            RelationshipKind::Aggregation
+        } else if self.line == LineStyle::Dotted {
+            RelationshipKind::Dependency
+        } else {
+            RelationshipKind::Association
+        }
+    }
+}
+
+/// A class linked to the relationship between two elements, like `(Estudiante, Curso) .. Inscripcion`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PlantUMLAssociationClass<'a> {
+    /// References of the two elements of the association.
+    pub ends: (&'a str, &'a str),
+    /// Reference of the association class.
+    pub class: &'a str,
+    /// Index in [`crate::PlantUMLFile::relationships`] of the relationship between both ends, it's
+    /// `None` when the diagram doesn't declare one.
+    pub relationship: Option<usize>,
+}
+
+/// A diamond node used to draw n-ary associations, declared with `<> nombre` or `diamond nombre`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PlantUMLDiamond<'a> {
+    pub name: &'a str,
+    /// References of the elements connected to the diamond by a relationship.
+    pub participants: Vec<&'a str>,
+}
+
+/// Parses a reference to an element, either quoted or as a single word. Qualified names like
+/// `com.acme.Equipo` or `a::b::C` are accepted.
+pub fn parse_reference(element: &str) -> IResult<&str, &str> {
+    alt((
+        parse_quoted,
+        recognize(separated_list1(
+            tag("::"),
+            take_while1(|c: char| c.is_alphanumeric() || c == '_' || c == '.' || c == '$'),
+        )),
+    ))(element)
+}
+
+fn parse_left_head(element: &str) -> IResult<&str, ArrowHead> {
+    map(
+        opt(alt((
+            value(ArrowHead::Extension, tag("<|")),
+            value(ArrowHead::Lollipop, tag("()")),
+            value(ArrowHead::Arrow, char('<')),
+            value(ArrowHead::Nested, char('+')),
+            value(ArrowHead::Composition, char('*')),
+            value(ArrowHead::Aggregation, char('o')),
+        ))),
+        |head| head.unwrap_or(ArrowHead::None),
+    )(element)
+}
+
+fn parse_right_head(element: &str) -> IResult<&str, ArrowHead> {
+    map(
+        opt(alt((
+            value(ArrowHead::Extension, tag("|>")),
+            value(ArrowHead::Lollipop, tag("()")),
+            value(ArrowHead::Arrow, char('>')),
+            value(ArrowHead::Nested, char('+')),
+            value(ArrowHead::Composition, char('*')),
+            value(ArrowHead::Aggregation, char('o')),
+        ))),
+        |head| head.unwrap_or(ArrowHead::None),
+    )(element)
+}
+
+fn parse_line(element: &str) -> IResult<&str, LineStyle> {
+    map(take_while1(|c: char| c == '-' || c == '.'), |line: &str| {
+        if line.contains('.') {
+            LineStyle::Dotted
+        } else {
+            LineStyle::Solid
+        }
+    })(element)
+}
+
+/// Parses a direction written inside an arrow. The line has to go on after it, so the name of an
+/// element written right after the arrow, like `usuario` in `A --usuario`, isn't a direction.
+fn parse_direction(element: &str) -> IResult<&str, Direction> {
+    terminated(
+        alt((
+            value(Direction::Up, alt((tag("up"), tag("u")))),
+            value(Direction::Down, alt((tag("down"), tag("do"), tag("d")))),
+            value(Direction::Left, alt((tag("left"), tag("le"), tag("l")))),
+            value(Direction::Right, alt((tag("right"), tag("ri"), tag("r")))),
+        )),
+        peek(parse_line),
+    )(element)
+}
+
+/// Decorations written inside an arrow, either between brackets or as a direction.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ArrowDecorations<'a> {
+    color: Option<&'a str>,
+    stroke: Option<Stroke>,
+    direction: Option<Direction>,
+    hidden: bool,
+}
+
+fn parse_decorations(element: &str) -> IResult<&str, ArrowDecorations<'_>> {
+    if let Ok((rest, direction)) = parse_direction(element) {
+        return Ok((
+            rest,
+            ArrowDecorations {
+                direction: Some(direction),
+                ..ArrowDecorations::default()
+            },
+        ));
+    }
+
+    let (rest, options) = delimited(
+        char('['),
+        take_till(|c: char| c == ']' || c == '\n'),
+        char(']'),
+    )(element)?;
+    let mut decorations = ArrowDecorations::default();
+    for option in options.split(',').map(str::trim) {
+        match option {
+            "dashed" => decorations.stroke = Some(Stroke::Dashed),
+            "dotted" => decorations.stroke = Some(Stroke::Dotted),
+            "bold" => decorations.stroke = Some(Stroke::Bold),
+            "plain" => decorations.stroke = Some(Stroke::Plain),
+            "hidden" => decorations.hidden = true,
+            color if color.starts_with('#') => decorations.color = Some(color),
+            _ => {}
+        }
+    }
+    Ok((rest, decorations))
+}
+
+/// Parses the line of an arrow with the decorations it may have in the middle, like `-[#red]-` or
+/// `-up-`.
+fn parse_arrow_line(element: &str) -> IResult<&str, (LineStyle, ArrowDecorations<'_>)> {
+    let (rest, line) = parse_line(element)?;
+    match parse_decorations(rest) {
+        Ok((rest, decorations)) => {
+            let (rest, _) = opt(parse_line)(rest)?;
+            Ok((rest, (line, decorations)))
+        }
+        Err(_) => Ok((rest, (line, ArrowDecorations::default()))),
+    }
+}
+
+fn parse_cardinality(element: &str) -> IResult<&str, Option<&str>> {
+    opt(terminated(parse_quoted, space0))(element)
+}
+
+/// Parses the cardinality of the element on the right side. A quoted string is only a cardinality
+/// when a reference follows it, otherwise it's the quoted name of the element.
+fn parse_right_cardinality(element: &str) -> IResult<&str, Option<&str>> {
+    opt(terminated(
+        parse_quoted,
+        pair(space0, peek(pair(parse_qualifiers, parse_reference))),
+    ))(element)
+}
+
+fn parse_qualifiers(element: &str) -> IResult<&str, Vec<&str>> {
+    map(
+        opt(terminated(
+            delimited(
+                char('['),
+                take_till(|c: char| c == ']' || c == '\n'),
+                char(']'),
+            ),
+            space0,
+        )),
+        |qualifiers: Option<&str>| {
+            qualifiers
+                .map(|qualifiers| {
+                    qualifiers
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|qualifier| !qualifier.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default()
+        },
+    )(element)
+}
+
+pub fn parse_relationship(element: &str) -> IResult<&str, PlantUMLRelationship<'_>> {
+    let (rest, from) = terminated(parse_reference, space0)(element.trim_start())?;
+    let (rest, from_qualifiers) = parse_qualifiers(rest)?;
+    let (rest, from_cardinality) = parse_cardinality(rest)?;
+    let (rest, (from_head, (line, decorations), to_head)) =
+        tuple((parse_left_head, parse_arrow_line, parse_right_head))(rest)?;
+    let (rest, to_cardinality) = parse_right_cardinality(space0(rest)?.0)?;
+    let (rest, to_qualifiers) = parse_qualifiers(rest)?;
+    let (rest, to) = parse_reference(rest)?;
+    let (rest, label) = opt(preceded(
+        tuple((space0, char(':'), space0)),
+        not_line_ending,
+    ))(rest)?;
+    let (rest, _) = preceded(space0, line_terminator)(rest)?;
+
+    Ok((
+        rest,
+        PlantUMLRelationship {
+            from,
+            to,
+            from_head,
+            to_head,
+            line,
+            from_cardinality,
+            to_cardinality,
+            from_qualifiers,
+            to_qualifiers,
+            label: label.map(str::trim).filter(|label| !label.is_empty()),
+            color: decorations.color,
+            stroke: decorations.stroke,
+            direction: decorations.direction,
+            hidden: decorations.hidden,
+        },
+    ))
+}
+
+/// Parses an association class, both `(A, B) .. C` and `C .. (A, B)` are accepted.
+pub fn parse_association_class(element: &str) -> IResult<&str, PlantUMLAssociationClass<'_>> {
+    let ends = |element| {
+        delimited(
+            pair(char('('), space0),
+            separated_pair(
+                parse_reference,
+                tuple((space0, char(','), space0)),
+                parse_reference,
+            ),
+            pair(space0, char(')')),
+        )(element)
+    };
+    let line = tuple((space0, parse_line, space0));
+
+    let (rest, (ends, class)) = alt((
+        separated_pair(ends, line, parse_reference),
+        map(
+            separated_pair(parse_reference, tuple((space0, parse_line, space0)), ends),
+            |(class, ends)| (ends, class),
+        ),
+    ))(element.trim_start())?;
+    let (rest, _) = preceded(space0, line_terminator)(rest)?;
+
+    Ok((
+        rest,
+        PlantUMLAssociationClass {
+            ends,
+            class,
+            relationship: None,
+        },
+    ))
+}
+
+/// Parses the declaration of a diamond node, its participants are found once the whole file is parsed.
+pub fn parse_diamond(element: &str) -> IResult<&str, PlantUMLDiamond<'_>> {
+    let (rest, name) = preceded(
+        pair(alt((tag("<>"), tag("diamond"))), space1),
+        parse_reference,
+    )(element.trim_start())?;
+    let (rest, _) = preceded(space0, line_terminator)(rest)?;
+
+    Ok((
+        rest,
+        PlantUMLDiamond {
+            name,
+            participants: vec![],
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_relationship_works() {
+        let (rest, relationship) =
+            parse_relationship("Equipo \"1\" *-- \"many\" Jugador : tiene\n")
+                .expect("Couldn't parse the relationship!");
+        assert_eq!("", rest);
+        assert_eq!(
+            relationship,
+            PlantUMLRelationship {
+                from: "Equipo",
+                to: "Jugador",
+                from_head: ArrowHead::Composition,
+                to_head: ArrowHead::None,
+                line: LineStyle::Solid,
+                from_cardinality: Some("1"),
+                to_cardinality: Some("many"),
+                from_qualifiers: vec![],
+                to_qualifiers: vec![],
+                label: Some("tiene"),
+                color: None,
+                stroke: None,
+                direction: None,
+                hidden: false,
+            }
+        );
+        assert_eq!(relationship.kind(), RelationshipKind::Composition);
+    }
+
+    #[test]
+    fn parse_relationship_kinds_works() {
+        let cases = [
+            ("Jugador --|> Persona", RelationshipKind::Extension),
+            ("Persona <|-- Jugador", RelationshipKind::Extension),
+            ("Equipo ..|> Comparable", RelationshipKind::Implementation),
+            ("Liga o-- Equipo", RelationshipKind::Aggregation),
+            ("Ventana ..> Analizador", RelationshipKind::Dependency),
+            ("Ventana --> Analizador", RelationshipKind::Association),
+            ("Ventana -- Analizador", RelationshipKind::Association),
+            ("Interna --+ Externa", RelationshipKind::Nesting),
+            ("Jugador -up-|> Persona", RelationshipKind::Extension),
+            (
+                "Equipo .[#blue].|> Comparable",
+                RelationshipKind::Implementation,
+            ),
+        ];
+        for (input, kind) in cases {
+            let (_, relationship) = parse_relationship(input).expect(input);
+            assert_eq!(relationship.kind(), kind, "{}", input);
+        }
+    }
+
+    #[test]
+    fn parse_relationship_with_decorations_works() {
+        let (_, relationship) = parse_relationship("A -[#red, dashed]-> B\n")
+            .expect("Couldn't parse the relationship!");
+        assert_eq!(relationship.color, Some("#red"));
+        assert_eq!(relationship.stroke, Some(Stroke::Dashed));
+        assert_eq!(relationship.to_head, ArrowHead::Arrow);
+        assert!(!relationship.hidden);
+
+        let (_, relationship) =
+            parse_relationship("A -[hidden]- B\n").expect("Couldn't parse the relationship!");
+        assert!(relationship.hidden);
+        assert_eq!(relationship.to_head, ArrowHead::None);
+
+        for (input, direction) in [
+            ("A -up-|> B", Direction::Up),
+            ("A -d-> B", Direction::Down),
+            ("A -left- B", Direction::Left),
+            ("A .ri.> B", Direction::Right),
+        ] {
+            let (_, relationship) = parse_relationship(input).expect(input);
+            assert_eq!(relationship.direction, Some(direction), "{}", input);
+        }
+    }
+
+    #[test]
+    fn parse_relationship_to_names_like_directions_works() {
+        let (_, relationship) =
+            parse_relationship("A --usuario\n").expect("Couldn't parse the relationship!");
+        assert_eq!(relationship.to, "usuario");
+        assert_eq!(relationship.direction, None);
+
+        let (_, relationship) =
+            parse_relationship("A -->derecha\n").expect("Couldn't parse the relationship!");
+        assert_eq!(relationship.to, "derecha");
+        assert_eq!(relationship.to_head, ArrowHead::Arrow);
+        assert_eq!(relationship.direction, None);
+
+        let (_, relationship) =
+            parse_relationship("A -down->derecha\n").expect("Couldn't parse the relationship!");
+        assert_eq!(relationship.to, "derecha");
+        assert_eq!(relationship.direction, Some(Direction::Down));
+    }
+
+    #[test]
+    fn parse_relationship_with_qualifiers_works() {
+        let (_, relationship) = parse_relationship("Map [key: String] --> \"1\" Valor\n")
+            .expect("Couldn't parse the relationship!");
+        assert_eq!(relationship.from, "Map");
+        assert_eq!(relationship.from_qualifiers, vec!["key: String"]);
+        assert!(relationship.to_qualifiers.is_empty());
+        assert_eq!(relationship.to_cardinality, Some("1"));
+
+        let (_, relationship) = parse_relationship("Banco -- [cuenta: int, sucursal: int] Cliente")
+            .expect("Couldn't parse the relationship!");
+        assert_eq!(
+            relationship.to_qualifiers,
+            vec!["cuenta: int", "sucursal: int"]
+        );
+    }
+
+    #[test]
+    fn relationship_nesting_works() {
+        let (_, relationship) =
+            parse_relationship("Interna --+ Externa").expect("Couldn't parse the relationship!");
+        assert_eq!(relationship.nesting(), Some(("Externa", "Interna")));
+
+        let (_, relationship) =
+            parse_relationship("Externa +-- Interna").expect("Couldn't parse the relationship!");
+        assert_eq!(relationship.nesting(), Some(("Externa", "Interna")));
+
+        let (_, relationship) =
+            parse_relationship("Externa --> Interna").expect("Couldn't parse the relationship!");
+        assert_eq!(relationship.nesting(), None);
+    }
+
+    #[test]
+    fn parse_lollipop_relationship_works() {
+        let (_, relationship) =
+            parse_relationship("Foo ()- IBar\n").expect("Couldn't parse the relationship!");
+        assert_eq!(relationship.from_head, ArrowHead::Lollipop);
+        assert_eq!(relationship.kind(), RelationshipKind::Implementation);
+        assert_eq!(relationship.lollipop(), Some(("Foo", "IBar")));
+
+        let (_, relationship) =
+            parse_relationship("IBar -() Foo\n").expect("Couldn't parse the relationship!");
+        assert_eq!(relationship.to_head, ArrowHead::Lollipop);
+        assert_eq!(relationship.kind(), RelationshipKind::Usage);
+        assert_eq!(relationship.lollipop(), Some(("Foo", "IBar")));
+
+        let (_, relationship) =
+            parse_relationship("Foo --> IBar\n").expect("Couldn't parse the relationship!");
+        assert_eq!(relationship.lollipop(), None);
+    }
+
+    #[test]
+    fn parse_relationship_with_quoted_references_works() {
+        let (_, relationship) = parse_relationship("\"Cuenta Bancaria\" --> CB")
+            .expect("Couldn't parse the relationship!");
+        assert_eq!(relationship.from, "Cuenta Bancaria");
+        assert_eq!(relationship.to, "CB");
+        assert_eq!(relationship.label, None);
+    }
+
+    #[test]
+    fn parse_relationship_with_quoted_reference_on_the_right_works() {
+        let (_, relationship) = parse_relationship("CB --> \"Cuenta Bancaria\"\n")
+            .expect("Couldn't parse the relationship!");
+        assert_eq!(relationship.to, "Cuenta Bancaria");
+        assert_eq!(relationship.to_cardinality, None);
+
+        let (_, relationship) = parse_relationship("CB --> \"1..*\" \"Cuenta Bancaria\" : tiene\n")
+            .expect("Couldn't parse the relationship!");
+        assert_eq!(relationship.to, "Cuenta Bancaria");
+        assert_eq!(relationship.to_cardinality, Some("1..*"));
+        assert_eq!(relationship.label, Some("tiene"));
+
+        let (_, relationship) = parse_relationship("Banco \"1\" --> \"*\" Cuenta\n")
+            .expect("Couldn't parse the relationship!");
+        assert_eq!(relationship.to, "Cuenta");
+        assert_eq!(relationship.to_cardinality, Some("*"));
+    }
+
+    #[test]
+    fn parse_relationship_with_qualified_references_works() {
+        let (_, relationship) = parse_relationship("a::b::C --> com.acme.Equipo : usa")
+            .expect("Couldn't parse the relationship!");
+        assert_eq!(relationship.from, "a::b::C");
+        assert_eq!(relationship.to, "com.acme.Equipo");
+        assert_eq!(relationship.label, Some("usa"));
+    }
+
+    #[test]
+    fn parse_relationship_fails() {
+        assert!(parse_relationship("Equipo Jugador\n").is_err());
+        assert!(parse_relationship("Equipo --> \n").is_err());
+        assert!(parse_relationship("Equipo --> Jugador Persona\n").is_err());
+    }
+
+    #[test]
+    fn parse_association_class_works() {
+        let expected = PlantUMLAssociationClass {
+            ends: ("Estudiante", "Curso"),
+            class: "Inscripcion",
+            relationship: None,
+        };
+        let (rest, association) = parse_association_class("(Estudiante, Curso) .. Inscripcion\n")
+            .expect("Couldn't parse the association class!");
+        assert_eq!("", rest);
+        assert_eq!(association, expected);
+
+        let (_, association) = parse_association_class("Inscripcion .. ( Estudiante ,Curso )")
+            .expect("Couldn't parse the association class!");
+        assert_eq!(association, expected);
+    }
+
+    #[test]
+    fn parse_association_class_fails() {
+        assert!(parse_association_class("(Estudiante) .. Inscripcion\n").is_err());
+        assert!(parse_association_class("(Estudiante, Curso .. Inscripcion\n").is_err());
+        assert!(parse_association_class("(Estudiante, Curso) Inscripcion\n").is_err());
+    }
+
+    #[test]
+    fn parse_diamond_works() {
+        let expected = PlantUMLDiamond {
+            name: "matricula",
+            participants: vec![],
+        };
+        assert_eq!(parse_diamond("<> matricula\n"), Ok(("", expected)));
+        let (_, diamond) = parse_diamond("diamond matricula").expect("Couldn't parse the diamond!");
+        assert_eq!(diamond.name, "matricula");
+        assert!(parse_diamond("<>matricula\n").is_err());
+    }
+}
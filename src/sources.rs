@@ -0,0 +1,307 @@
+use crate::elements::starts_with_keyword;
+use crate::errors::ParseError;
+use crate::errors::ParseErrorKind;
+use crate::spans::Span;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// Extensions of the files [`crate::parser::Parser::read_dir`] reads.
+pub const DIAGRAM_EXTENSIONS: [&str; 3] = ["puml", "plantuml", "iuml"];
+
+/// Diagrams read from a directory, keyed by their path, with the error of the ones that couldn't be
+/// read. It owns their text, so it has to outlive what is parsed from them.
+pub type Sources = BTreeMap<PathBuf, io::Result<Source>>;
+
+/// A file read by a [`Source`], either the one that was loaded or one of its includes.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SourceFile {
+    /// Name of the file as it's shown in diagnostics, usually its path.
+    pub name: String,
+    pub contents: String,
+}
+
+impl SourceFile {
+    pub fn new(name: impl Into<String>, contents: impl Into<String>) -> Self {
+        SourceFile {
+            name: name.into(),
+            contents: contents.into(),
+        }
+    }
+}
+
+/// Finds the files of `!include` lines.
+pub trait IncludeResolver {
+    /// The file of `!include path` written in the file named `from`, `None` if it doesn't exist.
+    fn resolve(&self, path: &str, from: &str) -> Option<SourceFile>;
+}
+
+impl<F: Fn(&str, &str) -> Option<SourceFile>> IncludeResolver for F {
+    fn resolve(&self, path: &str, from: &str) -> Option<SourceFile> {
+        self(path, from)
+    }
+}
+
+/// Reads the included files from the file system, relative paths are relative to the directory of
+/// the file that includes them.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct FileSystemResolver;
+
+impl IncludeResolver for FileSystemResolver {
+    fn resolve(&self, path: &str, from: &str) -> Option<SourceFile> {
+        let path = path.trim_matches('"');
+        let path = match Path::new(from).parent() {
+            Some(directory) => directory.join(path),
+            None => Path::new(path).to_path_buf(),
+        };
+        let contents = fs::read_to_string(&path).ok()?;
+        Some(SourceFile::new(path.to_string_lossy(), contents))
+    }
+}
+
+/// Part of the text of a [`Source`] that was copied from one of its files, it goes until the start of
+/// the next segment.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+struct Segment {
+    start: usize,
+    file: usize,
+    file_start: usize,
+}
+
+/// The text of a diagram together with the files it was read from. It owns the text, so the parsed
+/// [`crate::PlantUMLFile`] can borrow from it.
+///
+/// When the includes are expanded the text is made of several files, positions in it can be turned
+/// back into positions in those files with [`Source::locate`] and [`Source::relocate`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Source {
+    text: String,
+    files: Vec<SourceFile>,
+    segments: Vec<Segment>,
+    include_errors: Vec<(Span, ParseErrorKind)>,
+}
+
+impl Source {
+    /// A source made of a single file, without expanding its includes.
+    pub fn new(name: impl Into<String>, contents: impl Into<String>) -> Self {
+        let file = SourceFile::new(name, contents);
+        Source {
+            text: file.contents.clone(),
+            files: vec![file],
+            segments: vec![Segment {
+                start: 0,
+                file: 0,
+                file_start: 0,
+            }],
+            include_errors: vec![],
+        }
+    }
+
+    /// A source whose `!include` lines are replaced by the files `resolver` finds. Lines that can't
+    /// be expanded are kept and reported when the source is parsed.
+    pub fn with_includes(
+        name: impl Into<String>, contents: impl Into<String>, resolver: &dyn IncludeResolver,
+        max_depth: usize,
+    ) -> Self {
+        let mut source = Source {
+            text: String::new(),
+            files: vec![SourceFile::new(name, contents)],
+            segments: vec![],
+            include_errors: vec![],
+        };
+        source.expand(0, 0, resolver, max_depth);
+        source
+    }
+
+    /// The text that is parsed.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Name of the file that was loaded.
+    pub fn name(&self) -> &str {
+        &self.files[0].name
+    }
+
+    /// The file that was loaded followed by the files it includes.
+    pub fn files(&self) -> &[SourceFile] {
+        &self.files
+    }
+
+    /// The file a position of the text was copied from, and the position inside of that file.
+    pub fn locate(&self, position: usize) -> (&SourceFile, usize) {
+        let segment = self
+            .segments
+            .iter()
+            .rev()
+            .find(|segment| segment.start <= position)
+            .unwrap_or(&self.segments[0]);
+        let file = &self.files[segment.file];
+        let position = (segment.file_start + position - segment.start).min(file.contents.len());
+        (file, position)
+    }
+
+    /// The same error with its span, line and column relative to the file it was found in, together
+    /// with that file.
+    pub fn relocate<'a>(&self, error: &ParseError<'a>) -> (&SourceFile, ParseError<'a>) {
+        let (file, start) = self.locate(error.span.start);
+        let end = (start + error.span.end - error.span.start).min(file.contents.len());
+        let span = Span::new(start, end);
+        let (line, column) = span.line_column(&file.contents);
+        let error = ParseError {
+            span,
+            line,
+            column,
+            ..*error
+        };
+        (file, error)
+    }
+
+    /// `!include` lines that couldn't be expanded, with what went wrong.
+    pub(crate) fn include_errors(&self) -> &[(Span, ParseErrorKind)] {
+        &self.include_errors
+    }
+
+    fn expand(&mut self, file: usize, depth: usize, resolver: &dyn IncludeResolver, max: usize) {
+        let contents = self.files[file].contents.clone();
+        // The byte order mark of the loaded file is skipped by the parser, the ones of the included
+        // files would end up in the middle of the text.
+        let mut file_start = match depth {
+            0 => 0,
+            _ if contents.starts_with('\u{feff}') => '\u{feff}'.len_utf8(),
+            _ => 0,
+        };
+        self.start_segment(file, file_start);
+        for line in contents[file_start..].split_inclusive('\n') {
+            let directive = line.trim();
+            if let Some(path) = include_path(directive) {
+                let included = if depth < max {
+                    resolver
+                        .resolve(path, &self.files[file].name)
+                        .ok_or(ParseErrorKind::UnresolvedInclude)
+                } else {
+                    Err(ParseErrorKind::IncludeTooDeep)
+                };
+                match included {
+                    Ok(included) => {
+                        self.files.push(included);
+                        self.expand(self.files.len() - 1, depth + 1, resolver, max);
+                        if !self.text.is_empty() && !self.text.ends_with('\n') {
+                            self.text.push('\n');
+                        }
+                        self.start_segment(file, file_start + line.len());
+                    }
+                    Err(kind) => {
+                        let start = self.text.len() + line.find(directive).unwrap_or_default();
+                        self.include_errors
+                            .push((Span::new(start, start + directive.len()), kind));
+                        self.text.push_str(line);
+                    }
+                }
+            } else {
+                self.text.push_str(line);
+            }
+            file_start += line.len();
+        }
+    }
+
+    fn start_segment(&mut self, file: usize, file_start: usize) {
+        let start = self.text.len();
+        if let Some(last) = self.segments.last_mut().filter(|last| last.start == start) {
+            last.file = file;
+            last.file_start = file_start;
+        } else {
+            self.segments.push(Segment {
+                start,
+                file,
+                file_start,
+            });
+        }
+    }
+}
+
+/// Path of an `!include` line.
+fn include_path(line: &str) -> Option<&str> {
+    if !starts_with_keyword(line, "!include") {
+        return None;
+    }
+    Some(line["!include".len()..].trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::ElementKind;
+
+    fn resolver(path: &str, _from: &str) -> Option<SourceFile> {
+        match path {
+            "jugador.iuml" => Some(SourceFile::new(
+                "jugador.iuml",
+                "class Jugador {\n  - nombre: String\n}",
+            )),
+            "ciclo.iuml" => Some(SourceFile::new("ciclo.iuml", "!include ciclo.iuml\n")),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn with_includes_works() {
+        let contents = "class Equipo\n  !include jugador.iuml\nclass Partido\n";
+        let source = Source::with_includes("equipo.puml", contents, &resolver, 8);
+        assert_eq!(
+            source.text(),
+            "class Equipo\nclass Jugador {\n  - nombre: String\n}\nclass Partido\n"
+        );
+        assert_eq!(source.files().len(), 2);
+        assert!(source.include_errors().is_empty());
+
+        let (file, position) = source.locate(source.text().find("nombre").unwrap());
+        assert_eq!((file.name.as_str(), position), ("jugador.iuml", 20));
+        let (file, position) = source.locate(source.text().find("Partido").unwrap());
+        assert_eq!((file.name.as_str(), position), ("equipo.puml", 43));
+        let (file, position) = source.locate(0);
+        assert_eq!((file.name.as_str(), position), ("equipo.puml", 0));
+    }
+
+    #[test]
+    fn with_includes_keeps_what_cant_be_expanded() {
+        let contents = "!include falta.iuml\n!include ciclo.iuml\n";
+        let source = Source::with_includes("equipo.puml", contents, &resolver, 3);
+        assert_eq!(source.text(), "!include falta.iuml\n!include ciclo.iuml\n");
+        assert_eq!(
+            source.include_errors(),
+            [
+                (Span::new(0, 19), ParseErrorKind::UnresolvedInclude),
+                (Span::new(20, 39), ParseErrorKind::IncludeTooDeep),
+            ]
+        );
+        assert_eq!(source.files().len(), 4);
+        assert_eq!(source.locate(25).0.name, "ciclo.iuml");
+    }
+
+    #[test]
+    fn relocate_works() {
+        let source = Source::with_includes(
+            "equipo.puml",
+            "class Equipo\n!include jugador.iuml\n",
+            &resolver,
+            8,
+        );
+        let text = source.text();
+        let start = text.find("- nombre").unwrap();
+        let error = ParseError::new(
+            text,
+            ParseErrorKind::InvalidIdentifier,
+            ElementKind::Class,
+            &text[start..start + 8],
+            None,
+        );
+        let (file, error) = source.relocate(&error);
+        assert_eq!(file.name, "jugador.iuml");
+        assert_eq!(error.span, Span::new(18, 26));
+        assert_eq!((error.line, error.column), (2, 3));
+        assert_eq!(error.text, "- nombre");
+    }
+}
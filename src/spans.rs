@@ -0,0 +1,119 @@
+use nom::combinator::consumed;
+use nom::multi::many0;
+use nom::IResult;
+
+/// Range of bytes of the input, `end` is exclusive.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Span { start, end }
+    }
+
+    /// Span of `slice` inside `source`, `slice` has to be a part of `source`.
+    pub fn of(source: &str, slice: &str) -> Self {
+        let start = slice.as_ptr() as usize - source.as_ptr() as usize;
+        debug_assert!(
+            start + slice.len() <= source.len(),
+            "The slice isn't part of the source"
+        );
+        Span::new(start, start + slice.len())
+    }
+
+    /// Span from the start of `from` to the start of `rest` inside `source`, without the whitespace
+    /// at the end. `from` is usually the input of a parser and `rest` what it didn't consume.
+    pub fn between(source: &str, from: &str, rest: &str) -> Self {
+        let start = Span::of(source, from).start;
+        let end = Span::of(source, rest).start;
+        Span::new(start, start + source[start..end].trim_end().len())
+    }
+
+    /// The same span moved `offset` bytes forward.
+    pub fn shifted(&self, offset: usize) -> Self {
+        Span::new(self.start + offset, self.end + offset)
+    }
+
+    /// The 1-based line and column where the span starts, columns are counted in characters and a
+    /// byte order mark at the start of `source` isn't one of them.
+    pub fn line_column(&self, source: &str) -> (usize, usize) {
+        let before = &source[..self.start];
+        let line = before.matches('\n').count() + 1;
+        let line_start = match before.rfind('\n') {
+            Some(i) => i + 1,
+            None if before.starts_with('\u{feff}') => '\u{feff}'.len_utf8(),
+            None => 0,
+        };
+        let column = before[line_start..].chars().count() + 1;
+        (line, column)
+    }
+}
+
+/// A node of the diagram that knows where it was parsed.
+///
+/// Parsers return nodes whose span is relative to their own input, parents shift them so they end up
+/// relative to the input of [`crate::parse_uml_from_contents`].
+pub trait Spanned {
+    fn span(&self) -> Span;
+
+    /// Moves the span of the node, and the spans of its children, `offset` bytes forward.
+    fn shift(&mut self, offset: usize);
+}
+
+/// Like `many0`, but the spans of the parsed nodes are made relative to the input of the returned
+/// parser instead of the input of each call to `parser`.
+pub fn many0_spanned<'a, T: Spanned>(
+    mut parser: impl FnMut(&'a str) -> IResult<&'a str, T>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<T>> {
+    move |element| {
+        let (rest, nodes) = many0(consumed(&mut parser))(element)?;
+        let nodes = nodes
+            .into_iter()
+            .map(|(consumed, mut node)| {
+                node.shift(Span::of(element, consumed).start);
+                node
+            })
+            .collect();
+        Ok((rest, nodes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn of_works() {
+        let source = "class Equipo {\n}";
+        assert_eq!(Span::of(source, &source[6..12]), Span::new(6, 12));
+        assert_eq!(Span::of(source, &source[source.len()..]), Span::new(16, 16));
+    }
+
+    #[test]
+    fn between_works() {
+        let source = "  - nombre: String  \n}";
+        let from = source.trim_start();
+        let rest = &source[source.find('\n').unwrap()..];
+        assert_eq!(Span::between(source, from, rest), Span::new(2, 18));
+        assert_eq!(Span::new(2, 18).shifted(10), Span::new(12, 28));
+    }
+
+    #[test]
+    fn line_column_works() {
+        let source = "class Año {\n  - nombre: String\n}";
+        assert_eq!(Span::new(0, 5).line_column(source), (1, 1));
+        assert_eq!(Span::new(6, 10).line_column(source), (1, 7));
+        let field = source.find("- nombre").unwrap();
+        assert_eq!(Span::new(field, field + 1).line_column(source), (2, 3));
+        assert_eq!(
+            Span::new(source.len(), source.len()).line_column(source),
+            (3, 2)
+        );
+
+        let source = "\u{feff}class Año {}";
+        assert_eq!(Span::new(9, 13).line_column(source), (1, 7));
+    }
+}
@@ -0,0 +1,152 @@
+use crate::relationships::Stroke;
+use nom::bytes::complete::take_till1;
+use nom::character::complete::char;
+use nom::error::Error;
+use nom::error::ErrorKind;
+use nom::error::ParseError;
+use nom::sequence::preceded;
+use nom::Err;
+use nom::IResult;
+
+/// Direction of a gradient, given by the character between both colors.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum GradientDirection {
+    /// `|`
+    Horizontal,
+    /// `-`
+    Vertical,
+    /// `/`
+    DiagonalUp,
+    /// `\`
+    DiagonalDown,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Background<'a> {
+    Color(&'a str),
+    Gradient {
+        from: &'a str,
+        to: &'a str,
+        direction: GradientDirection,
+    },
+}
+
+/// Inline style of an element, like `#pink;line:red;line.dashed;text:blue`.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct ElementStyle<'a> {
+    pub background: Option<Background<'a>>,
+    pub line_color: Option<&'a str>,
+    pub line_style: Option<Stroke>,
+    pub text_color: Option<&'a str>,
+}
+
+fn parse_background(color: &str) -> Background<'_> {
+    let color = color.trim_start_matches('#');
+    let gradient = color.char_indices().skip(1).find_map(|(i, c)| {
+        let direction = match c {
+            '|' => GradientDirection::Horizontal,
+            '-' => GradientDirection::Vertical,
+            '/' => GradientDirection::DiagonalUp,
+            '\\' => GradientDirection::DiagonalDown,
+            _ => return None,
+        };
+        Some((i, direction))
+    });
+
+    match gradient {
+        Some((i, direction)) => Background::Gradient {
+            from: &color[..i],
+            to: color[i + 1..].trim_start_matches('#'),
+            direction,
+        },
+        None => Background::Color(color),
+    }
+}
+
+/// Parses the inline style of an element, it starts with `#` and its properties are separated by `;`.
+pub fn parse_element_style(element: &str) -> IResult<&str, ElementStyle<'_>> {
+    let (rest, properties) = preceded(
+        char('#'),
+        take_till1(|c: char| c.is_whitespace() || c == '{'),
+    )(element)?;
+
+    let mut style = ElementStyle::default();
+    for (i, property) in properties.split(';').enumerate() {
+        let property = property.trim_start_matches('#');
+        match property.split_once(':') {
+            Some(("back", color)) => style.background = Some(parse_background(color)),
+            Some(("line", color)) => style.line_color = Some(color.trim_start_matches('#')),
+            Some(("text", color)) => style.text_color = Some(color.trim_start_matches('#')),
+            Some(_) => return Err(Err::Error(Error::from_error_kind(property, ErrorKind::Tag))),
+            None => match property {
+                "line.dashed" => style.line_style = Some(Stroke::Dashed),
+                "line.dotted" => style.line_style = Some(Stroke::Dotted),
+                "line.bold" => style.line_style = Some(Stroke::Bold),
+                "line.plain" => style.line_style = Some(Stroke::Plain),
+                color if i == 0 && !color.is_empty() => {
+                    style.background = Some(parse_background(color))
+                }
+                _ => return Err(Err::Error(Error::from_error_kind(property, ErrorKind::Tag))),
+            },
+        }
+    }
+    Ok((rest, style))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_element_style_works() {
+        let (rest, style) = parse_element_style("#pink;line:red;line.dashed;text:blue {\n")
+            .expect("Couldn't parse the style!");
+        assert_eq!(" {\n", rest);
+        assert_eq!(
+            style,
+            ElementStyle {
+                background: Some(Background::Color("pink")),
+                line_color: Some("red"),
+                line_style: Some(Stroke::Dashed),
+                text_color: Some("blue"),
+            }
+        );
+
+        let (_, style) =
+            parse_element_style("##FF0000;line:#00FF00").expect("Couldn't parse the style!");
+        assert_eq!(style.background, Some(Background::Color("FF0000")));
+        assert_eq!(style.line_color, Some("00FF00"));
+    }
+
+    #[test]
+    fn parse_element_style_with_gradient_works() {
+        let (_, style) =
+            parse_element_style("#back:palegreen|lightblue").expect("Couldn't parse the style!");
+        assert_eq!(
+            style.background,
+            Some(Background::Gradient {
+                from: "palegreen",
+                to: "lightblue",
+                direction: GradientDirection::Horizontal
+            })
+        );
+
+        let (_, style) = parse_element_style("#red\\#blue").expect("Couldn't parse the style!");
+        assert_eq!(
+            style.background,
+            Some(Background::Gradient {
+                from: "red",
+                to: "blue",
+                direction: GradientDirection::DiagonalDown
+            })
+        );
+    }
+
+    #[test]
+    fn parse_element_style_fails() {
+        assert!(parse_element_style("pink").is_err());
+        assert!(parse_element_style("#").is_err());
+        assert!(parse_element_style("#pink;border:red").is_err());
+        assert!(parse_element_style("#pink;line.wavy").is_err());
+    }
+}
@@ -0,0 +1,83 @@
+/// Words that start the lines of a diagram, used to suggest the right one when a line can't be
+/// recognized.
+pub const KEYWORDS: [&str; 20] = [
+    "class",
+    "interface",
+    "enum",
+    "package",
+    "namespace",
+    "note",
+    "diamond",
+    "together",
+    "title",
+    "header",
+    "footer",
+    "caption",
+    "legend",
+    "skinparam",
+    "hide",
+    "show",
+    "remove",
+    "restore",
+    "url",
+    "set",
+];
+
+/// Levenshtein distance between two words, counted in characters.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != *b);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// The candidate closest to `word` when it's close enough to be a typo of it, less than a third of
+/// the candidate can be wrong. Candidates shorter than four characters are close to almost anything,
+/// so they're never suggested, and nothing is suggested when `word` is one of the candidates.
+pub fn closest<'c>(word: &str, candidates: impl IntoIterator<Item = &'c str>) -> Option<&'c str> {
+    candidates
+        .into_iter()
+        .filter(|candidate| candidate.chars().count() >= 4)
+        .map(|candidate| (edit_distance(word, candidate), candidate))
+        .filter(|(distance, candidate)| distance * 3 < candidate.chars().count())
+        .min_by_key(|(distance, _)| *distance)
+        .filter(|(distance, _)| *distance > 0)
+        .map(|(_, candidate)| candidate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edit_distance_works() {
+        assert_eq!(edit_distance("clas", "class"), 1);
+        assert_eq!(edit_distance("interfase", "interface"), 1);
+        assert_eq!(edit_distance("{abstact}", "{abstract}"), 1);
+        assert_eq!(edit_distance("año", "ano"), 1);
+        assert_eq!(edit_distance("", "enum"), 4);
+        assert_eq!(edit_distance("Equipo", "Equipo"), 0);
+    }
+
+    #[test]
+    fn closest_works() {
+        assert_eq!(closest("clas", KEYWORDS), Some("class"));
+        assert_eq!(closest("enumm", KEYWORDS), Some("enum"));
+        assert_eq!(closest("interfase", KEYWORDS), Some("interface"));
+        assert_eq!(closest("Equipo", KEYWORDS), None);
+        assert_eq!(closest("class", KEYWORDS), None);
+        assert_eq!(closest("Jugador", ["Jugador", "Jugadora"]), None);
+        assert_eq!(closest("Jugadr", ["Equipo", "Jugador"]), Some("Jugador"));
+        assert_eq!(closest("C", ["A", "B"]), None);
+        assert_eq!(closest("Gol", ["Gola", "Sol"]), Some("Gola"));
+        assert_eq!(closest("Ga", ["Gola", "Sol"]), None);
+        assert_eq!(closest("Equipa", ["Equipo"]), Some("Equipo"));
+    }
+}
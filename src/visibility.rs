@@ -0,0 +1,485 @@
+use crate::accessibilities::Accessibility;
+use crate::elements::line_terminator;
+use crate::elements::parse_stereotype;
+use crate::elements::parse_tag;
+use crate::notes::NoteTarget;
+use crate::relationships::parse_reference;
+use crate::PlantUMLElement;
+use crate::PlantUMLFile;
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::character::complete::char;
+use nom::character::complete::space0;
+use nom::character::complete::space1;
+use nom::combinator::map;
+use nom::combinator::opt;
+use nom::combinator::value;
+use nom::sequence::preceded;
+use nom::sequence::terminated;
+use nom::sequence::tuple;
+use nom::IResult;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DirectiveAction {
+    /// `hide`, the element isn't drawn but it's still used for the layout.
+    Hide,
+    /// `show`, undoes a `hide`.
+    Show,
+    /// `remove`, the element is taken out of the diagram.
+    Remove,
+    /// `restore`, undoes a `remove`.
+    Restore,
+}
+
+/// What a directive applies to.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DirectiveTarget<'a> {
+    /// Every element, it's the case of `hide members` and `remove *`.
+    All,
+    /// Reference to an element, resolve it with [`crate::PlantUMLFile::find_element`].
+    Element(&'a str),
+    /// Elements with a stereotype, like `<<Entity>>`.
+    Stereotype(&'a str),
+    /// Elements with a tag, like `$internal`.
+    Tag(&'a str),
+    /// Elements that aren't part of any relationship, `@unlinked`.
+    Unlinked,
+    Classes,
+    Interfaces,
+    Enums,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MemberKind {
+    Members,
+    /// `fields` or `attributes`.
+    Fields,
+    Methods,
+}
+
+/// The part of the targeted elements a directive applies to.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DirectiveScope<'a> {
+    /// The whole element.
+    Element,
+    /// Members of the elements, like `private fields`. When `empty` is `true` it only applies to the
+    /// compartments that have no members.
+    Members {
+        kind: MemberKind,
+        accessibility: Option<Accessibility>,
+        empty: bool,
+    },
+    /// A part of how the elements are drawn that isn't in the model, like `circle` or `stereotype`.
+    Decoration(&'a str),
+}
+
+/// A directive like `hide empty members`, `show <<Entity>> fields` or `remove $internal`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct PlantUMLDirective<'a> {
+    pub action: DirectiveAction,
+    pub target: DirectiveTarget<'a>,
+    pub scope: DirectiveScope<'a>,
+}
+
+fn parse_action(element: &str) -> IResult<&str, DirectiveAction> {
+    alt((
+        value(DirectiveAction::Hide, tag("hide")),
+        value(DirectiveAction::Show, tag("show")),
+        value(DirectiveAction::Remove, tag("remove")),
+        value(DirectiveAction::Restore, tag("restore")),
+    ))(element)
+}
+
+fn parse_target(element: &str) -> IResult<&str, DirectiveTarget<'_>> {
+    alt((
+        value(DirectiveTarget::All, char('*')),
+        value(DirectiveTarget::Unlinked, tag("@unlinked")),
+        map(parse_stereotype, DirectiveTarget::Stereotype),
+        map(parse_tag, DirectiveTarget::Tag),
+        map(parse_reference, |reference| match reference {
+            "class" => DirectiveTarget::Classes,
+            "interface" => DirectiveTarget::Interfaces,
+            "enum" => DirectiveTarget::Enums,
+            reference => DirectiveTarget::Element(reference),
+        }),
+    ))(element)
+}
+
+fn parse_member_scope(element: &str) -> IResult<&str, DirectiveScope<'_>> {
+    let (rest, (empty, accessibility, kind)) = tuple((
+        opt(terminated(tag("empty"), space1)),
+        opt(terminated(
+            alt((
+                value(Accessibility::Private, tag("private")),
+                value(Accessibility::Protected, tag("protected")),
+                value(Accessibility::Public, tag("public")),
+            )),
+            space1,
+        )),
+        alt((
+            value(MemberKind::Members, tag("members")),
+            value(MemberKind::Fields, tag("fields")),
+            value(MemberKind::Fields, tag("attributes")),
+            value(MemberKind::Methods, tag("methods")),
+        )),
+    ))(element)?;
+
+    Ok((
+        rest,
+        DirectiveScope::Members {
+            kind,
+            accessibility,
+            empty: empty.is_some(),
+        },
+    ))
+}
+
+fn parse_scope(element: &str) -> IResult<&str, DirectiveScope<'_>> {
+    terminated(
+        alt((
+            parse_member_scope,
+            map(
+                alt((
+                    tag("circle"),
+                    tag("stereotypes"),
+                    tag("stereotype"),
+                    tag("spot"),
+                )),
+                DirectiveScope::Decoration,
+            ),
+        )),
+        preceded(space0, line_terminator),
+    )(element)
+}
+
+/// Parses a `hide`, `show`, `remove` or `restore` directive.
+pub fn parse_directive(element: &str) -> IResult<&str, PlantUMLDirective<'_>> {
+    let (rest, action) = terminated(parse_action, space1)(element.trim_start())?;
+
+    // hide empty members
+    if let Ok((rest, scope)) = parse_scope(rest) {
+        let directive = PlantUMLDirective {
+            action,
+            target: DirectiveTarget::All,
+            scope,
+        };
+        return Ok((rest, directive));
+    }
+
+    let (rest, target) = parse_target(rest)?;
+    let (rest, scope) = alt((
+        preceded(space1, parse_scope),
+        map(preceded(space0, line_terminator), |_| {
+            DirectiveScope::Element
+        }),
+    ))(rest)?;
+
+    Ok((
+        rest,
+        PlantUMLDirective {
+            action,
+            target,
+            scope,
+        },
+    ))
+}
+
+/// What remains visible of an element after applying the directives.
+struct ElementVisibility {
+    hidden: bool,
+    removed: bool,
+    /// Indexed by [`accessibility_index`].
+    fields: [bool; 3],
+    methods: [bool; 3],
+}
+
+fn accessibility_index(accessibility: Accessibility) -> usize {
+    match accessibility {
+        Accessibility::Private => 0,
+        Accessibility::Protected => 1,
+        Accessibility::Public => 2,
+    }
+}
+
+impl<'a> PlantUMLFile<'a> {
+    /// Returns a copy of the file with only what the diagram shows once its `hide`, `show`, `remove`
+    /// and `restore` directives are applied.
+    ///
+    /// Hidden and removed elements are left out together with their relationships and notes, and so
+    /// are the hidden members of the remaining elements. Directives that only change how elements are
+    /// drawn, like `hide circle` or `hide empty members`, don't change the model.
+    pub fn visible_model(&self) -> PlantUMLFile<'a> {
+        let visibilities: Vec<ElementVisibility> = self
+            .elements()
+            .map(|element| self.element_visibility(&element))
+            .collect();
+        let is_visible = |reference: &str| match self.find_element(reference) {
+            Some(found) => self
+                .elements()
+                .zip(&visibilities)
+                .find(|(element, _)| *element == found)
+                .is_none_or(|(_, visibility)| !visibility.hidden && !visibility.removed),
+            None => true,
+        };
+
+        let mut new_indexes = vec![];
+        let mut relationships = vec![];
+        for relationship in self.relationships.iter() {
+            if is_visible(relationship.from) && is_visible(relationship.to) {
+                new_indexes.push(Some(relationships.len()));
+                relationships.push(relationship.clone());
+            } else {
+                new_indexes.push(None);
+            }
+        }
+
+        let mut visibilities = visibilities.iter();
+        let mut classes = vec![];
+        for class in self.classes.iter() {
+            let visibility = visibilities.next().expect("Every class has a visibility");
+            if visibility.hidden || visibility.removed {
+                continue;
+            }
+            let mut class = class.clone();
+            class
+                .fields
+                .retain(|field| visibility.fields[accessibility_index(field.accessibility)]);
+            class
+                .methods
+                .retain(|method| visibility.methods[accessibility_index(method.accessibility)]);
+            classes.push(class);
+        }
+        let mut interfaces = vec![];
+        for interface in self.interfaces.iter() {
+            let visibility = visibilities
+                .next()
+                .expect("Every interface has a visibility");
+            if visibility.hidden || visibility.removed {
+                continue;
+            }
+            let mut interface = interface.clone();
+            interface
+                .methods
+                .retain(|method| visibility.methods[accessibility_index(method.accessibility)]);
+            interfaces.push(interface);
+        }
+        let mut enums = vec![];
+        for enu in self.enums.iter() {
+            let visibility = visibilities.next().expect("Every enum has a visibility");
+            if !visibility.hidden && !visibility.removed {
+                enums.push(enu.clone());
+            }
+        }
+
+        let mut notes = vec![];
+        for note in self.notes.iter() {
+            let mut note = note.clone();
+            match note.target {
+                NoteTarget::Element(element) | NoteTarget::Member { element, .. }
+                    if !is_visible(element) =>
+                {
+                    continue
+                }
+                NoteTarget::Relationship(i) => match new_indexes[i] {
+                    Some(i) => note.target = NoteTarget::Relationship(i),
+                    None => continue,
+                },
+                _ => {}
+            }
+            note.links.retain(|link| is_visible(link));
+            notes.push(note);
+        }
+
+        let mut association_classes = vec![];
+        for association in self.association_classes.iter() {
+            let mut association = association.clone();
+            if !is_visible(association.class) {
+                continue;
+            }
+            match association.relationship.map(|i| new_indexes[i]) {
+                Some(Some(i)) => association.relationship = Some(i),
+                Some(None) => continue,
+                None => {}
+            }
+            association_classes.push(association);
+        }
+
+        let mut diamonds = self.diamonds.clone();
+        for diamond in diamonds.iter_mut() {
+            diamond
+                .participants
+                .retain(|participant| is_visible(participant));
+        }
+
+        let mut groups = self.groups.clone();
+        for group in groups.iter_mut() {
+            group.elements.retain(|element| is_visible(element));
+        }
+
+        PlantUMLFile {
+            classes,
+            interfaces,
+            enums,
+            packages: self.packages.clone(),
+            groups,
+            relationships,
+            association_classes,
+            diamonds,
+            notes,
+            directives: self.directives.clone(),
+            metadata: self.metadata.clone(),
+            namespace_separator: self.namespace_separator,
+            comments: self.comments.clone(),
+        }
+    }
+
+    fn element_visibility(&self, element: &PlantUMLElement) -> ElementVisibility {
+        let mut visibility = ElementVisibility {
+            hidden: false,
+            removed: false,
+            fields: [true; 3],
+            methods: [true; 3],
+        };
+        for directive in self.directives.iter() {
+            if !self.directive_applies_to(directive, element) {
+                continue;
+            }
+            let visible = matches!(
+                directive.action,
+                DirectiveAction::Show | DirectiveAction::Restore
+            );
+            match directive.scope {
+                DirectiveScope::Element => match directive.action {
+                    DirectiveAction::Hide | DirectiveAction::Show => visibility.hidden = !visible,
+                    DirectiveAction::Remove | DirectiveAction::Restore => {
+                        visibility.removed = !visible
+                    }
+                },
+                DirectiveScope::Members {
+                    kind,
+                    accessibility,
+                    empty: false,
+                } => {
+                    let indexes = match accessibility {
+                        Some(accessibility) => {
+                            let i = accessibility_index(accessibility);
+                            i..i + 1
+                        }
+                        None => 0..3,
+                    };
+                    for i in indexes {
+                        if kind != MemberKind::Methods {
+                            visibility.fields[i] = visible;
+                        }
+                        if kind != MemberKind::Fields {
+                            visibility.methods[i] = visible;
+                        }
+                    }
+                }
+                DirectiveScope::Members { empty: true, .. } | DirectiveScope::Decoration(_) => {}
+            }
+        }
+        visibility
+    }
+
+    fn directive_applies_to(
+        &self, directive: &PlantUMLDirective, element: &PlantUMLElement,
+    ) -> bool {
+        match directive.target {
+            DirectiveTarget::All => true,
+            DirectiveTarget::Element(reference) => self.find_element(reference) == Some(*element),
+            DirectiveTarget::Stereotype(stereotype) => element.stereotypes().contains(&stereotype),
+            DirectiveTarget::Tag(tag) => element.tags().contains(&tag),
+            DirectiveTarget::Unlinked => !self.relationships.iter().any(|relationship| {
+                [relationship.from, relationship.to]
+                    .iter()
+                    .any(|reference| self.find_element(reference) == Some(*element))
+            }),
+            DirectiveTarget::Classes => matches!(element, PlantUMLElement::Class(_)),
+            DirectiveTarget::Interfaces => matches!(element, PlantUMLElement::Interface(_)),
+            DirectiveTarget::Enums => matches!(element, PlantUMLElement::Enum(_)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_directive_works() {
+        assert_eq!(
+            parse_directive("hide empty members\nclass Foo"),
+            Ok((
+                "class Foo",
+                PlantUMLDirective {
+                    action: DirectiveAction::Hide,
+                    target: DirectiveTarget::All,
+                    scope: DirectiveScope::Members {
+                        kind: MemberKind::Members,
+                        accessibility: None,
+                        empty: true,
+                    },
+                }
+            ))
+        );
+        assert_eq!(
+            parse_directive("hide Foo methods\n"),
+            Ok((
+                "",
+                PlantUMLDirective {
+                    action: DirectiveAction::Hide,
+                    target: DirectiveTarget::Element("Foo"),
+                    scope: DirectiveScope::Members {
+                        kind: MemberKind::Methods,
+                        accessibility: None,
+                        empty: false,
+                    },
+                }
+            ))
+        );
+        assert_eq!(
+            parse_directive("show <<Entity>> private fields"),
+            Ok((
+                "",
+                PlantUMLDirective {
+                    action: DirectiveAction::Show,
+                    target: DirectiveTarget::Stereotype("Entity"),
+                    scope: DirectiveScope::Members {
+                        kind: MemberKind::Fields,
+                        accessibility: Some(Accessibility::Private),
+                        empty: false,
+                    },
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_directive_targets_works() {
+        let target = |input| parse_directive(input).map(|(_, directive)| directive.target);
+        assert_eq!(target("remove @unlinked\n"), Ok(DirectiveTarget::Unlinked));
+        assert_eq!(
+            target("remove $internal\n"),
+            Ok(DirectiveTarget::Tag("internal"))
+        );
+        assert_eq!(target("restore *\n"), Ok(DirectiveTarget::All));
+        assert_eq!(target("hide interface\n"), Ok(DirectiveTarget::Interfaces));
+        assert_eq!(
+            target("hide \"Cuenta Bancaria\"\n"),
+            Ok(DirectiveTarget::Element("Cuenta Bancaria"))
+        );
+
+        let (_, directive) = parse_directive("hide <<Entity>> circle").unwrap();
+        assert_eq!(directive.scope, DirectiveScope::Decoration("circle"));
+        let (_, directive) = parse_directive("hide Foo").unwrap();
+        assert_eq!(directive.scope, DirectiveScope::Element);
+    }
+
+    #[test]
+    fn parse_directive_fails() {
+        assert!(parse_directive("hidden Foo\n").is_err());
+        assert!(parse_directive("hide Foo bar\n").is_err());
+        assert!(parse_directive("hide\n").is_err());
+    }
+}